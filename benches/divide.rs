@@ -0,0 +1,127 @@
+//! Benchmarks for hand division (via scoring) and wait enumeration.
+//!
+//! Single-suit (chinitsu) hands with many-sided waits are the worst case for division, since
+//! nearly every sequence and triplet in the suit is a candidate meld. The `divide_*` groups compare
+//! the suit tables against the brute-force search they replaced.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
+use toitoi::{
+    hand::{decompose, waits},
+    score::score,
+    tile::{tile_from_string, tiles_from_string, Tile},
+    types::HandContext,
+};
+
+// The previous division, for comparison: every combination (with replacement) of candidate melds
+// for each candidate pair, keeping those the hand holds. Returns the number of divisions.
+fn brute_force_divide(tiles: &[Tile]) -> usize {
+    let mut pairs: Vec<Vec<Tile>> = vec![vec![]];
+    for tile in tiles.iter().sorted().dedup() {
+        if tiles.iter().filter(|&t| t == tile).count() >= 2 {
+            pairs.push(vec![*tile, *tile]);
+        }
+    }
+    let mut n_divisions = 0;
+    for pair in pairs {
+        let remaining = without(tiles, &pair);
+        if remaining.len() < 3 {
+            n_divisions += 1;
+            continue;
+        }
+        let melds: Vec<Vec<Tile>> =
+            candidate_melds().into_iter().filter(|meld| holds(&remaining, meld)).collect();
+        for combi in melds.iter().combinations_with_replacement(remaining.len() / 3) {
+            let combi_tiles: Vec<Tile> = combi.into_iter().flatten().cloned().collect();
+            if !holds(&remaining, &combi_tiles) {
+                continue;
+            }
+            if pair.is_empty() && remaining.len() - combi_tiles.len() == 2 {
+                continue;
+            }
+            n_divisions += 1;
+        }
+    }
+    n_divisions
+}
+
+fn candidate_melds() -> Vec<Vec<Tile>> {
+    let mut melds = vec![];
+    for suit in ["m", "p", "s"].iter() {
+        for n in 1..=7 {
+            melds.push(tiles_from_string(&format!("{}{}{}{}", n, n + 1, n + 2, suit)));
+        }
+    }
+    for tile in tiles_from_string("123456789m123456789p123456789s1234567z") {
+        melds.push(vec![tile; 3]);
+    }
+    melds
+}
+
+fn holds(tiles: &[Tile], needed: &[Tile]) -> bool {
+    needed.iter().all(|n| {
+        tiles.iter().filter(|&t| t == n).count() >= needed.iter().filter(|&t| t == n).count()
+    })
+}
+
+fn without(tiles: &[Tile], to_remove: &[Tile]) -> Vec<Tile> {
+    let mut result = tiles.to_vec();
+    for tile in to_remove {
+        if let Some(i) = result.iter().position(|t| t == tile) {
+            result.remove(i);
+        }
+    }
+    result
+}
+
+fn bench_score(c: &mut Criterion, name: &str, tiles: &str, winning_tile: &str) {
+    let tiles = tiles_from_string(tiles);
+    let context = HandContext {
+        winning_tile: tile_from_string(winning_tile),
+        is_tsumo: true,
+        ..Default::default()
+    };
+    c.bench_function(name, |b| b.iter(|| score(black_box(&tiles), &vec![], black_box(&context))));
+}
+
+fn bench_divide(c: &mut Criterion, name: &str, tiles: &str) {
+    let tiles = tiles_from_string(tiles);
+    let mut group = c.benchmark_group(name);
+    group.bench_function("suit_tables", |b| b.iter(|| decompose(black_box(&tiles), &[])));
+    group.bench_function("brute_force", |b| b.iter(|| brute_force_divide(black_box(&tiles))));
+    group.finish();
+}
+
+fn bench_waits(c: &mut Criterion, name: &str, tiles: &str) {
+    let tiles = tiles_from_string(tiles);
+    c.bench_function(name, |b| b.iter(|| waits(black_box(&tiles), &[])));
+}
+
+fn divide_benchmark(c: &mut Criterion) {
+    // Happoubijin (8-sided wait)
+    bench_score(c, "chinitsu_8sided", "1113334567888m", "5m");
+    // Junsei chuuren poutou (9-sided wait)
+    bench_score(c, "chinitsu_9sided", "1112345678999m", "5m");
+    // Many iipeikou-like interpretations
+    bench_score(c, "chinitsu_iipeikou", "2233445566778p", "8p");
+    // Mixed suits for comparison
+    bench_score(c, "mixed_suits", "234456m66p12344s", "4s");
+    // Not a winning hand
+    bench_score(c, "not_winning", "1113334567888m", "1p");
+}
+
+fn comparison_benchmark(c: &mut Criterion) {
+    bench_divide(c, "divide_chinitsu_8sided", "11133345678885m");
+    bench_divide(c, "divide_chinitsu_9sided", "11123455678999m");
+    bench_divide(c, "divide_chinitsu_iipeikou", "22334455667788p");
+    bench_divide(c, "divide_mixed_suits", "234456m66p123444s");
+}
+
+fn waits_benchmark(c: &mut Criterion) {
+    bench_waits(c, "waits_chinitsu_8sided", "1113334567888m");
+    bench_waits(c, "waits_chinitsu_9sided", "1112345678999m");
+    bench_waits(c, "waits_noten", "1357m2468p1357s9m");
+}
+
+criterion_group!(benches, divide_benchmark, comparison_benchmark, waits_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,236 @@
+//! Human-readable score breakdowns.
+
+use crate::{
+    score::ScoreResult,
+    tile::tiles_to_string,
+    types::{BreakdownFormat, BreakdownStyle, HanReason, Limit, Meld, MeldKind},
+};
+use itertools::Itertools;
+
+pub(crate) fn breakdown(
+    result: &ScoreResult, style: BreakdownStyle, format: BreakdownFormat,
+) -> String {
+    match style {
+        BreakdownStyle::Compact => compact(result, format),
+        BreakdownStyle::Verbose => verbose(result, format),
+    }
+}
+
+// A single line, e.g. "123m 456p 789s 234s 55p (4s ron, Ryanmen wait): Riichi 1 han, ... = 2 han
+// 30 fu, 2000"
+fn compact(result: &ScoreResult, format: BreakdownFormat) -> String {
+    let hand = match format {
+        BreakdownFormat::Text => hand_string(result),
+        BreakdownFormat::Markdown => format!("`{}`", hand_string(result)),
+    };
+    let reasons: Vec<String> = han_reasons(result)
+        .iter()
+        .map(|(reason, han)| format!("{} {} han", reason, han))
+        .chain(result.fu_reasons().iter().map(|(reason, fu)| format!("{} {} fu", reason, fu)))
+        .collect();
+    let total = match format {
+        BreakdownFormat::Text => total_string(result),
+        BreakdownFormat::Markdown => format!("**{}**", total_string(result)),
+    };
+    format!("{} ({}): {} = {}", hand, win_string(result), reasons.join(", "), total)
+}
+
+fn verbose(result: &ScoreResult, format: BreakdownFormat) -> String {
+    let mut lines = vec![];
+    match format {
+        BreakdownFormat::Text => {
+            lines.push(format!("Hand: {}", hand_string(result)));
+            lines.push(format!("Win: {}", win_string(result)));
+            lines.push(String::from("Yaku:"));
+            for (reason, han) in han_reasons(result) {
+                lines.push(format!("  {}: {} han", reason, han));
+            }
+            if !result.fu_reasons().is_empty() {
+                lines.push(String::from("Fu:"));
+                for (reason, fu) in result.fu_reasons() {
+                    lines.push(format!("  {}: {} fu", reason, fu));
+                }
+            }
+            lines.push(format!("Total: {}", total_string(result)));
+        }
+        BreakdownFormat::Markdown => {
+            lines.push(format!("- **Hand:** `{}`", hand_string(result)));
+            lines.push(format!("- **Win:** {}", win_string(result)));
+            lines.push(String::new());
+            lines.push(String::from("| Yaku | Han |"));
+            lines.push(String::from("| --- | --: |"));
+            for (reason, han) in han_reasons(result) {
+                lines.push(format!("| {} | {} |", reason, han));
+            }
+            if !result.fu_reasons().is_empty() {
+                lines.push(String::new());
+                lines.push(String::from("| Fu | Fu |"));
+                lines.push(String::from("| --- | --: |"));
+                for (reason, fu) in result.fu_reasons() {
+                    lines.push(format!("| {} | {} |", reason, fu));
+                }
+            }
+            lines.push(String::new());
+            lines.push(format!("**Total:** {}", total_string(result)));
+        }
+    }
+    lines.join("\n")
+}
+
+// Repeated reasons (dora, double winds) are combined
+fn han_reasons(result: &ScoreResult) -> Vec<(HanReason, u8)> {
+    let mut combined: Vec<(HanReason, u8)> = vec![];
+    for (reason, han) in result.han_reasons() {
+        match combined.iter_mut().find(|(r, _)| *r == reason) {
+            Some((_, total)) => *total += han,
+            None => combined.push((reason, han)),
+        }
+    }
+    combined
+}
+
+// The closed melds, the pair, then the calls: closed quads in brackets and called melds in
+// parentheses. Chiitoitsu and kokushi musou are shown as a single group of tiles.
+fn hand_string(result: &ScoreResult) -> String {
+    let pair = match result.pair() {
+        Some(pair) => pair,
+        None => {
+            let mut tiles = result.remaining();
+            tiles.sort();
+            return tiles_to_string(&tiles);
+        }
+    };
+    // Quads can only come from calls
+    let (calls, closed): (Vec<Meld>, Vec<Meld>) =
+        result.melds().into_iter().partition(|m| m.is_open() || m.kind() == MeldKind::Quad);
+    closed.iter().chain(std::iter::once(&pair)).chain(&calls).map(meld_string).join(" ")
+}
+
+fn meld_string(meld: &Meld) -> String {
+    let tiles = tiles_to_string(meld.tiles());
+    if meld.is_open() {
+        format!("({})", tiles)
+    } else if meld.kind() == MeldKind::Quad {
+        format!("[{}]", tiles)
+    } else {
+        tiles
+    }
+}
+
+// E.g. "4s ron, Ryanmen wait"
+fn win_string(result: &ScoreResult) -> String {
+    let win = if result.is_tsumo() { "tsumo" } else { "ron" };
+    format!("{} {}, {} wait", result.context().winning_tile, win, result.wait())
+}
+
+// E.g. "2 han 30 fu, 2000" or "6 han 30 fu, Haneman 12000"
+fn total_string(result: &ScoreResult) -> String {
+    let fu = if result.fu() > 0 { format!(" {} fu", result.fu()) } else { String::new() };
+    match result.limit() {
+        Limit::NoLimit => format!("{} han{}, {}", result.han(), fu, result.points()),
+        limit => format!("{} han{}, {} {}", result.han(), fu, limit, result.points()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        score::score,
+        tile::{tile_from_string, tiles_from_string},
+        types::{Call, HandContext},
+    };
+
+    fn result(tiles: &str, calls: &Vec<Call>, context: &HandContext) -> ScoreResult {
+        score(&tiles_from_string(tiles), calls, context).remove(0)
+    }
+
+    fn ct(winning_tile: &str, is_tsumo: bool) -> HandContext {
+        HandContext {
+            winning_tile: tile_from_string(winning_tile),
+            is_tsumo,
+            player_wind: tile_from_string("2z"),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compact() {
+        let calls = vec![Call::ankan(tile_from_string("1s")), Call::ankan(tile_from_string("7z"))];
+        let context = HandContext { round_wind: tile_from_string("2z"), ..ct("1z", false) };
+        let r = result("456m1122z", &calls, &context);
+        let reasons = "Chun 1 han, Base 20 fu, Open triplet (honours) 4 fu, Yakuhai pair (round \
+                       wind) 2 fu, Yakuhai pair (player wind) 2 fu, Closed quad (terminals) 32 \
+                       fu, Closed quad (honours) 32 fu, Closed ron 10 fu, Round up 8 fu";
+
+        assert_eq!(
+            breakdown(&r, BreakdownStyle::Compact, BreakdownFormat::Text),
+            format!(
+                "456m 111z 22z [1111s] [7777z] (1z ron, Shanpon wait): {} = 1 han 110 fu, 3600",
+                reasons
+            )
+        );
+        assert_eq!(
+            breakdown(&r, BreakdownStyle::Compact, BreakdownFormat::Markdown),
+            format!(
+                "`456m 111z 22z [1111s] [7777z]` (1z ron, Shanpon wait): {} = **1 han 110 fu, \
+                 3600**",
+                reasons
+            )
+        );
+    }
+
+    #[test]
+    fn test_verbose() {
+        let context = HandContext { is_riichi: true, n_dora: 2, ..ct("4s", true) };
+        let r = result("123m456p789s55p23s", &vec![], &context);
+
+        assert_eq!(
+            breakdown(&r, BreakdownStyle::Verbose, BreakdownFormat::Text),
+            [
+                "Hand: 123m 456p 789s 234s 55p",
+                "Win: 4s tsumo, Ryanmen wait",
+                "Yaku:",
+                "  Menzen tsumo: 1 han",
+                "  Riichi: 1 han",
+                "  Dora: 2 han",
+                "  Pinfu: 1 han",
+                "Fu:",
+                "  Base: 20 fu",
+                "Total: 5 han 20 fu, Mangan 2000/4000",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            breakdown(&r, BreakdownStyle::Verbose, BreakdownFormat::Markdown),
+            [
+                "- **Hand:** `123m 456p 789s 234s 55p`",
+                "- **Win:** 4s tsumo, Ryanmen wait",
+                "",
+                "| Yaku | Han |",
+                "| --- | --: |",
+                "| Menzen tsumo | 1 |",
+                "| Riichi | 1 |",
+                "| Dora | 2 |",
+                "| Pinfu | 1 |",
+                "",
+                "| Fu | Fu |",
+                "| --- | --: |",
+                "| Base | 20 |",
+                "",
+                "**Total:** 5 han 20 fu, Mangan 2000/4000",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_hand_string() {
+        let r =
+            result("123m456p78s55s", &vec![Call::pon(tile_from_string("7z"))], &ct("9s", false));
+        assert_eq!(hand_string(&r), "123m 456p 789s 55s (777z)");
+        // Chiitoitsu
+        let r = result("1122m3344p5566s7z", &vec![], &ct("7z", false));
+        assert_eq!(hand_string(&r), "1122m3344p5566s77z");
+    }
+}
@@ -1,36 +1,47 @@
 //! Hand calculator.
 
 use crate::{
-    division::{divide, Division},
+    division::{divide, is_hand_complete, Division},
     fu::fu_for_division,
-    meld::no_open_calls,
-    tile::Tile,
-    types::{Call, FuReason, HanReason, HandContext, Yaku, Yakuman},
+    meld::{no_open_calls, wait_shape},
+    tile::{tile_counts, tile_from_string, Tile},
+    types::{
+        Call, FuReason, HanReason, HandContext, Interpretation, Meld, MeldOrigin, Wait, Yaku,
+        Yakuman,
+    },
     yaku::{yaku_in_hand, yakuman_in_hand, Checkable},
 };
 use itertools::{repeat_n, Itertools};
 
 /// Stores calculation results.
-#[derive(Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd)]
 pub(crate) struct CalcResult {
     pub(crate) winning_tile: Tile,
-    pub(crate) pair: Vec<Tile>,
-    pub(crate) melds: Vec<Vec<Tile>>,
+    pub(crate) pair: Option<Meld>,
+    pub(crate) melds: Vec<Meld>,
     pub(crate) remaining: Vec<Tile>,
     pub(crate) fu_reasons: Vec<(FuReason, u8)>,
     pub(crate) han_reasons: Vec<(HanReason, u8)>,
+    pub(crate) wait: Wait,
+    // The meld (or pair) completed by the winning tile, if the hand has a standard shape
+    pub(crate) winning_meld: Option<Meld>,
 }
 
 /// Calculates fu and han for all winning hand configurations.
 pub(crate) fn calculate(
     tiles: &Vec<Tile>, calls: &Vec<Call>, context: &HandContext,
 ) -> Vec<CalcResult> {
-    let mut results: Vec<CalcResult> = divide(tiles)
+    // Skip dividing the hand altogether if it can't be completed
+    let mut all_tiles = tiles.to_vec();
+    all_tiles.push(context.winning_tile);
+    let divisions = if is_hand_complete(&all_tiles) { divide(tiles) } else { vec![] };
+
+    let mut results: Vec<CalcResult> = divisions
         .into_iter()
         .filter(|d| d.is_tenpai(calls.len()))
         .filter(|d| d.wins_on(&context.winning_tile))
         .map(|d| calculate_division(&d, calls, context))
-        .sorted()
+        .sorted_by(|a, b| order_key(a).cmp(&order_key(b)))
         .collect();
     // Separate checks for chiitoi and kokushi
     match check_chiitoi(tiles, calls, context) {
@@ -41,44 +52,77 @@ pub(crate) fn calculate(
         Some(result) => results.push(result),
         None => (),
     }
+    if context.rules.local_yaku {
+        if let Some(result) = check_shiisanpuutaa(tiles, calls, context) {
+            results.push(result);
+        }
+    }
     results
 }
 
+type OrderKey<'a> = (Tile, &'a [Tile], Vec<&'a [Tile]>, &'a [(FuReason, u8)]);
+
+// Orders results by their tiles, ignoring meld kinds and origins. A pair completed by the winning
+// tile (tanki) is keyed by the winning tile alone, so tanki results come before others with the
+// same pair.
+fn order_key(result: &CalcResult) -> OrderKey<'_> {
+    let pair = match &result.pair {
+        Some(pair) if result.winning_meld.as_ref() == Some(pair) => &pair.tiles()[..1],
+        Some(pair) => pair.tiles(),
+        None => &[],
+    };
+    (
+        result.winning_tile,
+        pair,
+        result.melds.iter().map(|m| m.tiles()).collect(),
+        &result.fu_reasons,
+    )
+}
+
 fn calculate_division(division: &Division, calls: &Vec<Call>, context: &HandContext) -> CalcResult {
     let fu_reasons = fu_for_division(&division, calls, context);
 
     // Add in winning tile for yaku calculation
-    let mut con = (*context).clone();
     let mut div = (*division).clone();
 
-    // Assume tanki if a pair is not formed
-    let mut winning_meld =
-        if division.pair.len() != 2 { division.pair.clone() } else { division.remaining.clone() };
-    winning_meld.push(context.winning_tile.clone());
-    winning_meld.sort();
-    con.winning_meld = winning_meld.clone();
+    // The winning tile completes the pair if one is not formed (tanki), or a meld otherwise
+    let mut winning_tiles = division.remaining.clone();
+    winning_tiles.push(context.winning_tile);
+    winning_tiles.sort();
+    let origin = if context.is_tsumo { MeldOrigin::Concealed } else { MeldOrigin::Ron };
+    let winning_meld = Meld::new(winning_tiles.clone(), origin);
 
-    if division.pair.len() != 2 {
-        div.pair = winning_meld;
+    let pair = if division.pair.len() != 2 {
+        div.pair = winning_tiles;
+        winning_meld.clone()
     } else {
-        div.melds.push(winning_meld);
-    }
+        div.melds.push(winning_meld.clone());
+        Meld::concealed(division.pair.clone())
+    };
     div.remaining = vec![];
 
-    let mut han_reasons = han_for_division(&div, calls, &con);
+    let mut han_reasons = han_for_division(&div, calls, context);
 
     // Check for pinfu
     if is_pinfu(&fu_reasons) {
         han_reasons.push((HanReason::Yaku(Yaku::Pinfu), Yaku::Pinfu.han_closed()));
     }
 
+    let mut melds = div.melds.clone();
+    melds.extend(calls.iter().map(|call| call.meld()));
+
+    let interpretation = Interpretation::Standard { pair: pair.tile(), melds: melds.clone() };
+    context.rules.custom_yaku.apply(&mut han_reasons, &interpretation, context);
+
     CalcResult {
         winning_tile: context.winning_tile.clone(),
-        pair: div.pair.clone(),
-        melds: div.melds.clone(),
+        pair: Some(pair),
+        melds,
         remaining: div.remaining.clone(),
         fu_reasons,
         han_reasons,
+        wait: wait_shape(&division.remaining),
+        winning_meld: Some(winning_meld),
     }
 }
 
@@ -119,6 +163,28 @@ fn han_for_division(
     reasons.append(
         &mut repeat_n((HanReason::Yaku(Yaku::Uradora), 1), context.n_uradora as usize).collect(),
     );
+    reasons.append(&mut nukidora_han(context));
+    reasons
+}
+
+// Each nukidora is worth 1 han, plus 1 han for each (ura)dora indicator pointing to north
+fn nukidora_han(context: &HandContext) -> Vec<(HanReason, u8)> {
+    let pei = tile_from_string("4z");
+    let sanma = context.rules.sanma.is_some();
+    let n_indicated =
+        |indicators: &[Tile]| indicators.iter().filter(|t| t.dora(sanma) == pei).count();
+    let n_nukidora = context.n_nukidora as usize;
+
+    let mut reasons: Vec<(HanReason, u8)> =
+        repeat_n((HanReason::Yaku(Yaku::Nukidora), 1), n_nukidora).collect();
+    reasons.extend(repeat_n(
+        (HanReason::Yaku(Yaku::Dora), 1),
+        n_nukidora * n_indicated(&context.dora_indicators),
+    ));
+    reasons.extend(repeat_n(
+        (HanReason::Yaku(Yaku::Uradora), 1),
+        n_nukidora * n_indicated(&context.uradora_indicators),
+    ));
     reasons
 }
 
@@ -133,14 +199,19 @@ fn check_chiitoi(
     let division = Division { pair: vec![], melds: vec![], remaining };
 
     if Yaku::Chiitoitsu.check(&division, calls, context) {
-        let han_reasons = han_for_division(&division, calls, context);
+        let mut han_reasons = han_for_division(&division, calls, context);
+        let pairs = division.remaining.iter().sorted().dedup().cloned().collect();
+        let interpretation = Interpretation::Chiitoitsu { pairs };
+        context.rules.custom_yaku.apply(&mut han_reasons, &interpretation, context);
         Some(CalcResult {
             winning_tile: context.winning_tile.clone(),
-            pair: division.pair,
-            melds: division.melds,
+            pair: None,
+            melds: vec![],
             remaining: division.remaining,
             fu_reasons: vec![(FuReason::Chiitoitsu, 25)],
             han_reasons,
+            wait: Wait::Tanki,
+            winning_meld: None,
         })
     } else {
         None
@@ -156,15 +227,79 @@ fn check_kokushi(
     let division = Division { pair: vec![], melds: vec![], remaining };
 
     if Yakuman::KokushiMusou.check(&division, calls, context) {
+        let mut han_reasons = vec![(HanReason::Yakuman(Yakuman::KokushiMusou), 13)];
+        if context.rules.aotenjou {
+            han_reasons.extend(contextual_han(&division, calls, context));
+        }
+        let counts = tile_counts(&division.remaining);
+        let pair = *division.remaining.iter().find(|t| counts[t.index()] == 2).unwrap();
+        let interpretation = Interpretation::Kokushi { pair };
+        context.rules.custom_yaku.apply(&mut han_reasons, &interpretation, context);
         Some(CalcResult {
             winning_tile: context.winning_tile.clone(),
-            pair: division.pair,
-            melds: division.melds,
+            pair: None,
+            melds: vec![],
             remaining: division.remaining,
-            fu_reasons: vec![],
-            han_reasons: vec![(HanReason::Yakuman(Yakuman::KokushiMusou), 13)],
+            fu_reasons: irregular_fu(context),
+            han_reasons,
+            wait: if tiles.iter().unique().count() == 13 { Wait::Kokushi13 } else { Wait::Tanki },
+            winning_meld: None,
         })
     } else {
         None
     }
 }
+
+fn check_shiisanpuutaa(
+    tiles: &Vec<Tile>, calls: &Vec<Call>, context: &HandContext,
+) -> Option<CalcResult> {
+    // Make a dummy division
+    let mut remaining = tiles.clone();
+    remaining.push(context.winning_tile.clone());
+    let division = Division { pair: vec![], melds: vec![], remaining };
+
+    if Yakuman::Shiisanpuutaa.check(&division, calls, context) {
+        let mut han_reasons = vec![(HanReason::Yakuman(Yakuman::Shiisanpuutaa), 13)];
+        if context.rules.aotenjou {
+            han_reasons.extend(contextual_han(&division, calls, context));
+        }
+        Some(CalcResult {
+            winning_tile: context.winning_tile.clone(),
+            pair: None,
+            melds: vec![],
+            remaining: division.remaining,
+            fu_reasons: irregular_fu(context),
+            han_reasons,
+            wait: Wait::Tanki,
+            winning_meld: None,
+        })
+    } else {
+        None
+    }
+}
+
+// Under aotenjou, yaku and yakuman from the hand context also count towards hands without
+// melds. Yaku from the hand composition do not apply to these dummy divisions.
+fn contextual_han(
+    division: &Division, calls: &Vec<Call>, context: &HandContext,
+) -> Vec<(HanReason, u8)> {
+    han_for_division(division, calls, context)
+        .into_iter()
+        .filter(|(reason, _)| match reason {
+            HanReason::Yaku(yaku) => yaku.is_contextual(),
+            HanReason::Yakuman(yakuman) => yakuman.is_contextual(),
+            HanReason::CustomYaku(_) | HanReason::CustomYakuman(_) => false,
+        })
+        .collect()
+}
+
+// Under aotenjou, hands without melds need fu too: 30 for a ron, or 22 (rounded up) for a tsumo
+fn irregular_fu(context: &HandContext) -> Vec<(FuReason, u8)> {
+    if !context.rules.aotenjou {
+        vec![]
+    } else if context.is_tsumo {
+        vec![(FuReason::Base, 20), (FuReason::TsumoNoPinfu, 2), (FuReason::RoundUp, 8)]
+    } else {
+        vec![(FuReason::Base, 20), (FuReason::ClosedRon, 10)]
+    }
+}
@@ -0,0 +1,260 @@
+//! A read-only catalog of every yaku and yakuman.
+//!
+//! With the `serde` feature, the catalog can be exported to JSON with `catalog_json`.
+
+use crate::{
+    types::{HanReason, Yaku, Yakuman},
+    yaku::{ALL_YAKU, ALL_YAKUMAN},
+};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Represents a broad category of yaku, for grouping in documentation and UIs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum YakuCategory {
+    /// Depends on how or when the hand was won, e.g. riichi or haitei.
+    Situational,
+    /// Depends on sequences, e.g. pinfu or ittsu.
+    Sequences,
+    /// Depends on triplets or quads, e.g. toitoi or sanankou.
+    Triplets,
+    /// Depends on terminals or simples, e.g. tanyao or chanta.
+    Terminals,
+    /// Depends on honours, e.g. yakuhai or daisangen.
+    Honours,
+    /// Depends on suits, e.g. honitsu or ryuuiisou.
+    Suits,
+    /// Has an irregular hand shape, e.g. chiitoitsu or kokushi musou.
+    Irregular,
+    /// Bonus han that are not yaku, e.g. dora.
+    Bonus,
+}
+
+/// Represents the metadata of a yaku or yakuman.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct YakuEntry {
+    han_reason: HanReason,
+    han_closed: u8,
+    han_open: u8,
+    is_closed_only: bool,
+    is_local: bool,
+    supersedes: Vec<HanReason>,
+    superseded_by: Vec<HanReason>,
+    category: YakuCategory,
+}
+
+impl YakuEntry {
+    pub fn han_reason(&self) -> HanReason { self.han_reason }
+
+    /// The han for a closed hand.
+    pub fn han_closed(&self) -> u8 { self.han_closed }
+
+    /// The han for an open hand, or 0 if the yaku requires a closed hand.
+    pub fn han_open(&self) -> u8 { self.han_open }
+
+    /// Whether the yaku requires a closed hand.
+    pub fn is_closed_only(&self) -> bool { self.is_closed_only }
+
+    /// Whether the yaku is only counted under local rules (see `Rules::local_yaku`).
+    pub fn is_local(&self) -> bool { self.is_local }
+
+    /// The yaku that are not counted when this one is.
+    pub fn supersedes(&self) -> Vec<HanReason> { self.supersedes.clone() }
+
+    /// The yaku that prevent this one from being counted.
+    pub fn superseded_by(&self) -> Vec<HanReason> { self.superseded_by.clone() }
+
+    pub fn category(&self) -> YakuCategory { self.category }
+}
+
+/// Lists every yaku then every yakuman, in declaration order.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     catalog::{catalog, YakuCategory},
+///     types::{HanReason, Yaku},
+/// };
+///
+/// let catalog = catalog();
+/// let chinitsu = catalog.iter().find(|e| e.han_reason() == HanReason::Yaku(Yaku::Chinitsu));
+/// let chinitsu = chinitsu.unwrap();
+///
+/// assert_eq!((chinitsu.han_closed(), chinitsu.han_open()), (6, 5));
+/// assert!(!chinitsu.is_closed_only());
+/// assert_eq!(
+///     chinitsu.supersedes(),
+///     vec![HanReason::Yaku(Yaku::Honitsu), HanReason::Yaku(Yaku::JunchanTaiyao)]
+/// );
+/// assert_eq!(chinitsu.category(), YakuCategory::Suits);
+/// ```
+pub fn catalog() -> Vec<YakuEntry> {
+    let yaku = ALL_YAKU.iter().map(|&y| {
+        let supersedes = y.supercedes().into_iter().map(HanReason::Yaku).collect();
+        let category = yaku_category(y);
+        entry(HanReason::Yaku(y), y.han_closed(), y.han_open(), y.is_local(), supersedes, category)
+    });
+    let yakuman = ALL_YAKUMAN.iter().map(|&y| {
+        let supersedes = y.supercedes().into_iter().map(HanReason::Yakuman).collect();
+        let category = yakuman_category(y);
+        let han_reason = HanReason::Yakuman(y);
+        entry(han_reason, y.han_closed(), y.han_open(), y.is_local(), supersedes, category)
+    });
+    let mut entries: Vec<YakuEntry> = yaku.chain(yakuman).collect();
+
+    // Invert the supersession lists
+    let supersessions: Vec<(HanReason, HanReason)> =
+        entries.iter().flat_map(|e| e.supersedes.iter().map(move |&s| (e.han_reason, s))).collect();
+    for entry in entries.iter_mut() {
+        entry.superseded_by = supersessions
+            .iter()
+            .filter(|(_, superseded)| *superseded == entry.han_reason)
+            .map(|&(by, _)| by)
+            .collect();
+    }
+    entries
+}
+
+/// Exports the catalog as pretty-printed JSON.
+#[cfg(feature = "serde")]
+pub fn catalog_json() -> String {
+    serde_json::to_string_pretty(&catalog()).expect("Catalog should serialize")
+}
+
+fn entry(
+    han_reason: HanReason, han_closed: u8, han_open: u8, is_local: bool,
+    supersedes: Vec<HanReason>, category: YakuCategory,
+) -> YakuEntry {
+    YakuEntry {
+        han_reason,
+        han_closed,
+        han_open,
+        is_closed_only: han_open == 0,
+        is_local,
+        supersedes,
+        superseded_by: vec![],
+        category,
+    }
+}
+
+fn yaku_category(yaku: Yaku) -> YakuCategory {
+    match yaku {
+        Yaku::MenzenTsumo
+        | Yaku::Riichi
+        | Yaku::Ippatsu
+        | Yaku::OpenRiichi
+        | Yaku::HaiteiRaoyue
+        | Yaku::HouteiRaoyui
+        | Yaku::RinshanKaihou
+        | Yaku::Chankan
+        | Yaku::DoubleRiichi
+        | Yaku::Renhou => YakuCategory::Situational,
+        Yaku::Pinfu | Yaku::Iipeikou | Yaku::SanshokuDoujun | Yaku::Ittsu | Yaku::Ryanpeikou => {
+            YakuCategory::Sequences
+        }
+        Yaku::Sankantsu
+        | Yaku::Toitoi
+        | Yaku::Sanankou
+        | Yaku::SanshokuDoukou
+        | Yaku::Sanrenkou => YakuCategory::Triplets,
+        Yaku::Tanyao | Yaku::Chantaiyao | Yaku::Honroutou | Yaku::JunchanTaiyao => {
+            YakuCategory::Terminals
+        }
+        Yaku::Ton
+        | Yaku::Nan
+        | Yaku::Sha
+        | Yaku::Pei
+        | Yaku::Haku
+        | Yaku::Hatsu
+        | Yaku::Chun
+        | Yaku::Shousangen => YakuCategory::Honours,
+        Yaku::Honitsu | Yaku::Chinitsu => YakuCategory::Suits,
+        Yaku::Chiitoitsu => YakuCategory::Irregular,
+        Yaku::Dora | Yaku::Akadora | Yaku::Uradora | Yaku::Nukidora => YakuCategory::Bonus,
+    }
+}
+
+fn yakuman_category(yakuman: Yakuman) -> YakuCategory {
+    match yakuman {
+        Yakuman::KazoeYakuman
+        | Yakuman::Tenhou
+        | Yakuman::Chiihou
+        | Yakuman::NagashiMangan
+        | Yakuman::OpenRiichiDealIn
+        | Yakuman::Ishinouenimosannen => YakuCategory::Situational,
+        Yakuman::IisouSuushun => YakuCategory::Sequences,
+        Yakuman::Suuankou | Yakuman::Suukantsu => YakuCategory::Triplets,
+        Yakuman::Chinroutou => YakuCategory::Terminals,
+        Yakuman::Daisangen
+        | Yakuman::Shousuushii
+        | Yakuman::Daisuushii
+        | Yakuman::Tsuuiisou
+        | Yakuman::Daichisei => YakuCategory::Honours,
+        Yakuman::Ryuuiisou | Yakuman::ChuurenPoutou | Yakuman::Daisharin => YakuCategory::Suits,
+        Yakuman::KokushiMusou | Yakuman::Shiisanpuutaa => YakuCategory::Irregular,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(han_reason: HanReason) -> YakuEntry {
+        catalog().into_iter().find(|e| e.han_reason() == han_reason).unwrap()
+    }
+
+    #[test]
+    fn test_catalog_complete() {
+        let catalog = catalog();
+        assert_eq!(
+            catalog.len(),
+            Yaku::OpenRiichi as usize + 1 + Yakuman::OpenRiichiDealIn as usize + 1
+        );
+        assert!(catalog.iter().all(|e| e.han_closed() > 0));
+    }
+
+    #[test]
+    fn test_catalog_closed_only() {
+        assert!(find(HanReason::Yaku(Yaku::Riichi)).is_closed_only());
+        assert!(find(HanReason::Yakuman(Yakuman::Suuankou)).is_closed_only());
+        assert!(!find(HanReason::Yaku(Yaku::Tanyao)).is_closed_only());
+        assert_eq!(find(HanReason::Yaku(Yaku::Honitsu)).han_open(), 2);
+    }
+
+    #[test]
+    fn test_catalog_superseded_by() {
+        assert_eq!(
+            find(HanReason::Yaku(Yaku::Chantaiyao)).superseded_by(),
+            vec![HanReason::Yaku(Yaku::Honroutou), HanReason::Yaku(Yaku::JunchanTaiyao)]
+        );
+        assert_eq!(
+            find(HanReason::Yakuman(Yakuman::Tsuuiisou)).superseded_by(),
+            vec![HanReason::Yakuman(Yakuman::Daichisei)]
+        );
+        assert!(find(HanReason::Yaku(Yaku::Chinitsu)).superseded_by().is_empty());
+    }
+
+    #[test]
+    fn test_catalog_local() {
+        assert!(find(HanReason::Yaku(Yaku::Renhou)).is_local());
+        assert!(find(HanReason::Yakuman(Yakuman::Shiisanpuutaa)).is_local());
+        assert!(find(HanReason::Yaku(Yaku::OpenRiichi)).is_local());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_catalog_json() {
+        let json: serde_json::Value = serde_json::from_str(&catalog_json()).unwrap();
+        let entries = json.as_array().unwrap();
+
+        assert_eq!(entries.len(), catalog().len());
+        assert_eq!(entries[1]["han_reason"], serde_json::json!({ "Yaku": "Riichi" }));
+        assert_eq!(entries[1]["han_closed"], 1);
+        assert_eq!(entries[1]["is_closed_only"], true);
+        assert_eq!(entries[1]["superseded_by"], serde_json::json!([{ "Yaku": "DoubleRiichi" }]));
+        assert_eq!(entries[1]["category"], "Situational");
+    }
+}
@@ -0,0 +1,235 @@
+//! User-defined yaku, for house rules.
+//!
+//! Custom yaku are registered in a `YakuRegistry`, which is set in `Rules::custom_yaku` and
+//! checked by `score` alongside the built-in yaku.
+
+use crate::types::{HanReason, HandContext, Interpretation};
+use std::{cmp::Ordering, fmt, sync::Arc};
+
+/// A user-defined yaku or yakuman.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     custom_yaku::{CustomYaku, YakuRegistry},
+///     score::score,
+///     tile::{tile_from_string, tiles_from_string},
+///     types::{HanReason, HandContext, Interpretation, Rules, Yaku},
+/// };
+///
+/// // All sequences in pinzu and souzu, superseding pinfu
+/// struct Sunflower;
+///
+/// impl CustomYaku for Sunflower {
+///     fn name(&self) -> &'static str { "Sunflower" }
+///
+///     fn han_closed(&self) -> u8 { 2 }
+///
+///     fn han_open(&self) -> u8 { 1 }
+///
+///     fn supersedes(&self) -> Vec<HanReason> { vec![HanReason::Yaku(Yaku::Pinfu)] }
+///
+///     fn check(&self, hand: &Interpretation, _context: &HandContext) -> bool {
+///         match hand {
+///             Interpretation::Standard { pair, melds } => {
+///                 !pair.is_man()
+///                     && !pair.is_honour()
+///                     && melds.iter().all(|m| m.is_sequence() && !m.tile().is_man())
+///             }
+///             _ => false,
+///         }
+///     }
+/// }
+///
+/// let mut registry = YakuRegistry::new();
+/// registry.register(Sunflower);
+/// let context = HandContext {
+///     winning_tile: tile_from_string("4s"),
+///     player_wind: tile_from_string("2z"),
+///     rules: Rules { custom_yaku: registry, ..Default::default() },
+///     ..Default::default()
+/// };
+///
+/// let results = score(&tiles_from_string("234p456p789s55p23s"), &vec![], &context);
+///
+/// assert_eq!(results[0].han_reasons(), vec![(HanReason::CustomYaku("Sunflower"), 2)]);
+/// ```
+pub trait CustomYaku: Send + Sync {
+    /// The name, which must be unique within a registry.
+    fn name(&self) -> &'static str;
+
+    /// Whether this is a yakuman. Yakuman are reported as `HanReason::CustomYakuman` and yaku as
+    /// `HanReason::CustomYaku`.
+    fn is_yakuman(&self) -> bool { false }
+
+    /// The han for a closed hand.
+    fn han_closed(&self) -> u8;
+
+    /// The han for an open hand, or 0 if the yaku requires a closed hand.
+    fn han_open(&self) -> u8;
+
+    /// The yaku (built-in or custom) that are not counted when this one is.
+    fn supersedes(&self) -> Vec<HanReason> { vec![] }
+
+    /// Whether the completed hand has the yaku.
+    ///
+    /// For standard interpretations, the melds include the calls (see `hand::decompose`), and the
+    /// meld or pair completed by the winning tile has origin `MeldOrigin::Ron` if won by ron.
+    fn check(&self, hand: &Interpretation, context: &HandContext) -> bool;
+}
+
+/// Represents a set of custom yaku.
+///
+/// Registries are compared by the names of their yaku.
+#[derive(Clone, Default)]
+pub struct YakuRegistry {
+    yaku: Vec<Arc<dyn CustomYaku>>,
+}
+
+impl YakuRegistry {
+    pub fn new() -> YakuRegistry { YakuRegistry::default() }
+
+    /// Adds a custom yaku.
+    ///
+    /// Panics if a yaku with the same name is already registered.
+    pub fn register<Y: CustomYaku + 'static>(&mut self, yaku: Y) {
+        assert!(!self.names().contains(&yaku.name()), "Yaku already registered");
+        self.yaku.push(Arc::new(yaku));
+    }
+
+    /// The names of the registered yaku, in order of registration.
+    pub fn names(&self) -> Vec<&'static str> { self.yaku.iter().map(|y| y.name()).collect() }
+
+    pub fn is_empty(&self) -> bool { self.yaku.is_empty() }
+
+    // Adds the custom yaku found in the hand, then removes the yaku they supersede
+    pub(crate) fn apply(
+        &self, han_reasons: &mut Vec<(HanReason, u8)>, hand: &Interpretation, context: &HandContext,
+    ) {
+        let is_closed = match hand {
+            Interpretation::Standard { melds, .. } => melds.iter().all(|m| !m.is_open()),
+            _ => true,
+        };
+        let found: Vec<&Arc<dyn CustomYaku>> =
+            self.yaku.iter().filter(|y| y.check(hand, context)).collect();
+        let superseded: Vec<HanReason> = found.iter().flat_map(|y| y.supersedes()).collect();
+        for yaku in found {
+            let han = if is_closed { yaku.han_closed() } else { yaku.han_open() };
+            if han > 0 {
+                han_reasons.push((custom_han_reason(yaku.as_ref()), han));
+            }
+        }
+        han_reasons.retain(|(reason, _)| !superseded.contains(reason));
+    }
+}
+
+fn custom_han_reason(yaku: &dyn CustomYaku) -> HanReason {
+    if yaku.is_yakuman() {
+        HanReason::CustomYakuman(yaku.name())
+    } else {
+        HanReason::CustomYaku(yaku.name())
+    }
+}
+
+impl fmt::Debug for YakuRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl PartialEq for YakuRegistry {
+    fn eq(&self, other: &Self) -> bool { self.names() == other.names() }
+}
+
+impl Eq for YakuRegistry {}
+
+impl PartialOrd for YakuRegistry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for YakuRegistry {
+    fn cmp(&self, other: &Self) -> Ordering { self.names().cmp(&other.names()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hand::decompose,
+        tile::{tile_from_string, tiles_from_string},
+        types::{Call, Yaku},
+    };
+
+    // Any hand containing the red dragon
+    struct Akachun {
+        supersedes: Vec<HanReason>,
+    }
+
+    impl CustomYaku for Akachun {
+        fn name(&self) -> &'static str { "Akachun" }
+
+        fn han_closed(&self) -> u8 { 2 }
+
+        fn han_open(&self) -> u8 { 0 }
+
+        fn supersedes(&self) -> Vec<HanReason> { self.supersedes.clone() }
+
+        fn check(&self, hand: &Interpretation, _context: &HandContext) -> bool {
+            let chun = tile_from_string("7z");
+            match hand {
+                Interpretation::Standard { pair, melds } => {
+                    *pair == chun || melds.iter().any(|m| m.tiles().contains(&chun))
+                }
+                Interpretation::Chiitoitsu { pairs } => pairs.contains(&chun),
+                Interpretation::Kokushi { .. } => true,
+            }
+        }
+    }
+
+    fn registry(supersedes: Vec<HanReason>) -> YakuRegistry {
+        let mut registry = YakuRegistry::new();
+        registry.register(Akachun { supersedes });
+        registry
+    }
+
+    #[test]
+    fn test_apply() {
+        let hand = &decompose(&tiles_from_string("123m456p789s11p777z"), &[])[0];
+        let context = HandContext::default();
+        let mut han_reasons = vec![(HanReason::Yaku(Yaku::Chun), 1)];
+
+        registry(vec![]).apply(&mut han_reasons, hand, &context);
+        assert_eq!(
+            han_reasons,
+            vec![(HanReason::Yaku(Yaku::Chun), 1), (HanReason::CustomYaku("Akachun"), 2)]
+        );
+    }
+
+    #[test]
+    fn test_apply_supersedes() {
+        let hand = &decompose(&tiles_from_string("123m456p789s11p777z"), &[])[0];
+        let context = HandContext::default();
+        let mut han_reasons = vec![(HanReason::Yaku(Yaku::Chun), 1)];
+
+        registry(vec![HanReason::Yaku(Yaku::Chun)]).apply(&mut han_reasons, hand, &context);
+        assert_eq!(han_reasons, vec![(HanReason::CustomYaku("Akachun"), 2)]);
+    }
+
+    #[test]
+    fn test_apply_closed_only() {
+        let calls = [Call::pon(tile_from_string("7z"))];
+        let hand = &decompose(&tiles_from_string("123m456p789s11p"), &calls)[0];
+        let mut han_reasons = vec![];
+
+        registry(vec![]).apply(&mut han_reasons, hand, &HandContext::default());
+        assert!(han_reasons.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_duplicate() {
+        let mut registry = registry(vec![]);
+        registry.register(Akachun { supersedes: vec![] });
+    }
+}
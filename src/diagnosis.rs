@@ -0,0 +1,234 @@
+//! Explanations for hands that do not score.
+
+use crate::{
+    hand::{shanten, waits},
+    hand_context::ContextError,
+    score::{is_kataagari, is_valid_sanma, score, ScoreResult},
+    tile::{tile_counts, tiles_to_string, Tile},
+    types::{Call, HandContext},
+};
+use std::fmt;
+
+/// Represents the reason a hand does not score.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ScoreFailure {
+    /// The input does not describe a possible hand.
+    InvalidInput(InvalidInput),
+    /// The hand is not tenpai, so no tile completes it. `shanten` is at least 1.
+    NotComplete { shanten: i8 },
+    /// The hand is tenpai, but every wait is a tile the player already holds four copies of
+    /// (karaten), so no tile completes it.
+    Karaten,
+    /// The hand is tenpai, but the winning tile is not one of its waits.
+    NotAWait { waits: Vec<Tile> },
+    /// The hand was won by ron on a wait, but one of its waits is in the player's discards.
+    Furiten { discarded_waits: Vec<Tile> },
+    /// The hand is complete, but has no yaku. Dora alone are not enough.
+    NoYaku,
+    /// Some of the hand's waits have no yaku, and `Rules::reject_kataagari` is set.
+    Kataagari,
+}
+
+/// Represents a problem with the input to `try_score`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InvalidInput {
+    /// The hand should have 13 tiles, less 3 for each call, not counting the winning tile.
+    TileCount(usize),
+    /// More than four copies of the tile are used.
+    TooManyCopies(Tile),
+    /// The hand uses 2-man to 8-man or chi calls under sanma rules.
+    SanmaTiles,
+    /// The context flags are inconsistent.
+    Context(ContextError),
+}
+
+impl fmt::Display for ScoreFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScoreFailure::InvalidInput(reason) => write!(f, "Invalid input: {}", reason),
+            ScoreFailure::NotComplete { shanten } => {
+                write!(f, "The hand is not complete ({} shanten)", shanten)
+            }
+            ScoreFailure::Karaten => write!(f, "The hand has no valid wait (karaten)"),
+            ScoreFailure::NotAWait { waits } => {
+                write!(f, "The winning tile is not a wait (waits: {})", tiles_to_string(waits))
+            }
+            ScoreFailure::Furiten { discarded_waits } => {
+                write!(f, "The hand is furiten ({} discarded)", tiles_to_string(discarded_waits))
+            }
+            ScoreFailure::NoYaku => write!(f, "The hand has no yaku"),
+            ScoreFailure::Kataagari => write!(f, "Some of the hand's waits have no yaku"),
+        }
+    }
+}
+
+impl fmt::Display for InvalidInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidInput::TileCount(n) => write!(f, "expected 13 tiles including calls, got {}", n),
+            InvalidInput::TooManyCopies(tile) => write!(f, "more than four copies of {}", tile),
+            InvalidInput::SanmaTiles => write!(f, "tiles or calls not used in sanma"),
+            InvalidInput::Context(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Scores a hand like `score`, or explains why it does not score.
+///
+/// Only the results with yaku are returned. Furiten is only checked if `discards` are given, and
+/// only against the discards themselves (not temporary or riichi furiten).
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     diagnosis::{try_score, ScoreFailure},
+///     tile::{tile_from_string, tiles_from_string},
+///     types::HandContext,
+/// };
+///
+/// let context = HandContext {
+///     winning_tile: tile_from_string("6m"),
+///     player_wind: tile_from_string("2z"),
+///     ..Default::default()
+/// };
+/// let tiles = tiles_from_string("123456m456p79s12z");
+///
+/// assert_eq!(
+///     try_score(&tiles, &vec![], &context, None),
+///     Err(ScoreFailure::NotComplete { shanten: 1 })
+/// );
+/// let tiles = tiles_from_string("123456m456p789s2z");
+/// assert_eq!(
+///     try_score(&tiles, &vec![], &context, None),
+///     Err(ScoreFailure::NotAWait { waits: vec![tile_from_string("2z")] })
+/// );
+/// ```
+pub fn try_score(
+    tiles: &Vec<Tile>, calls: &Vec<Call>, context: &HandContext, discards: Option<&[Tile]>,
+) -> Result<Vec<ScoreResult>, ScoreFailure> {
+    check_input(tiles, calls, context).map_err(ScoreFailure::InvalidInput)?;
+
+    let waits = waits(tiles, calls);
+    if waits.is_empty() {
+        return match shanten(tiles, calls) {
+            0 => Err(ScoreFailure::Karaten),
+            shanten => Err(ScoreFailure::NotComplete { shanten }),
+        };
+    }
+    if !waits.contains(&context.winning_tile) {
+        return Err(ScoreFailure::NotAWait { waits });
+    }
+    if let (Some(discards), false) = (discards, context.is_tsumo) {
+        let discarded_waits: Vec<Tile> =
+            waits.iter().filter(|w| discards.contains(w)).cloned().collect();
+        if !discarded_waits.is_empty() {
+            return Err(ScoreFailure::Furiten { discarded_waits });
+        }
+    }
+
+    if context.rules.reject_kataagari && is_kataagari(tiles, calls, context) {
+        return Err(ScoreFailure::Kataagari);
+    }
+
+    let mut results = score(tiles, calls, context);
+    results.retain(|r| r.has_yaku());
+    if results.is_empty() {
+        return Err(ScoreFailure::NoYaku);
+    }
+    Ok(results)
+}
+
+fn check_input(tiles: &[Tile], calls: &[Call], context: &HandContext) -> Result<(), InvalidInput> {
+    let n_tiles = tiles.len() + 3 * calls.len();
+    if n_tiles != 13 {
+        return Err(InvalidInput::TileCount(n_tiles));
+    }
+    let mut all_tiles = tiles.to_vec();
+    all_tiles.push(context.winning_tile);
+    all_tiles.extend(calls.iter().flat_map(|call| call.tiles()));
+    let counts = tile_counts(&all_tiles);
+    if let Some(tile) = all_tiles.iter().find(|t| counts[t.index()] > 4) {
+        return Err(InvalidInput::TooManyCopies(*tile));
+    }
+    if context.rules.sanma.is_some() && !is_valid_sanma(tiles, calls, context) {
+        return Err(InvalidInput::SanmaTiles);
+    }
+    context.validate().map_err(InvalidInput::Context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::{tile_from_string, tiles_from_string};
+
+    fn ct(winning_tile: &str, is_tsumo: bool) -> HandContext {
+        HandContext {
+            winning_tile: tile_from_string(winning_tile),
+            is_tsumo,
+            player_wind: tile_from_string("2z"),
+            ..Default::default()
+        }
+    }
+
+    fn failure(tiles: &str, calls: &Vec<Call>, context: &HandContext) -> ScoreFailure {
+        try_score(&tiles_from_string(tiles), calls, context, None).unwrap_err()
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            failure("123456m456p789s12z", &vec![], &ct("1z", false)),
+            ScoreFailure::InvalidInput(InvalidInput::TileCount(14))
+        );
+        assert_eq!(
+            failure("1111m456p789s234s", &vec![], &ct("1m", false)),
+            ScoreFailure::InvalidInput(InvalidInput::TooManyCopies(tile_from_string("1m")))
+        );
+        let context = HandContext { is_ippatsu: true, ..ct("1z", false) };
+        assert_eq!(
+            failure("123456m456p789s1z", &vec![], &context),
+            ScoreFailure::InvalidInput(InvalidInput::Context(ContextError::IppatsuWithoutRiichi))
+        );
+    }
+
+    #[test]
+    fn test_not_complete() {
+        assert_eq!(
+            failure("147m258p369s1234z", &vec![], &ct("5z", false)),
+            ScoreFailure::NotComplete { shanten: 6 }
+        );
+        assert_eq!(
+            failure("123456m456p789s1z", &vec![], &ct("2z", false)),
+            ScoreFailure::NotAWait { waits: tiles_from_string("1z") }
+        );
+    }
+
+    #[test]
+    fn test_karaten() {
+        // The only wait is a fifth 5-souzu
+        assert_eq!(failure("123m456p789s5555s", &vec![], &ct("4s", false)), ScoreFailure::Karaten);
+    }
+
+    #[test]
+    fn test_furiten() {
+        let tiles = tiles_from_string("123456m456p789s5s");
+        let discards = tiles_from_string("9m5s");
+        let context = HandContext { is_riichi: true, ..ct("5s", false) };
+        assert_eq!(
+            try_score(&tiles, &vec![], &context, Some(&discards)),
+            Err(ScoreFailure::Furiten { discarded_waits: tiles_from_string("5s") })
+        );
+        // Furiten does not prevent tsumo
+        let context = HandContext { is_tsumo: true, ..context };
+        assert!(try_score(&tiles, &vec![], &context, Some(&discards)).is_ok());
+    }
+
+    #[test]
+    fn test_no_yaku() {
+        let calls = vec![Call::pon(tile_from_string("9m"))];
+        let context = HandContext { n_dora: 3, ..ct("4s", false) };
+        assert_eq!(failure("123m456p23s55p", &calls, &context), ScoreFailure::NoYaku);
+        assert_eq!(ScoreFailure::NoYaku.to_string(), "The hand has no yaku");
+    }
+}
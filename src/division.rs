@@ -1,9 +1,11 @@
 use crate::{
     meld::{
-        is_kanchan, is_partial_meld, is_penchan, is_ryanmen, is_shanpon, kanchan_waits,
-        penchan_waits, possible_melds, ryanmen_waits, shanpon_waits, Meld,
+        is_kanchan, is_partial_meld, is_penchan, is_ryanmen, is_sequence, is_shanpon,
+        kanchan_waits, penchan_waits, ryanmen_waits, shanpon_waits,
     },
-    tile::{has_tiles, with_tiles_removed, Tile},
+    suit_table::{completing_tiles, is_complete, meld_splits},
+    tile::{tile_counts, with_tiles_removed, Tile},
+    types::Meld,
 };
 use itertools::Itertools;
 
@@ -18,11 +20,11 @@ impl Division {
     pub(crate) fn waits(&self) -> Vec<Tile> {
         let mut waits = vec![];
 
-        if self.pair.len() == 0 && self.remaining.len() == 1 {
+        if self.pair.is_empty() && self.remaining.len() == 1 {
             // Tanki
             waits.append(&mut self.remaining.clone());
         } else if self.pair.len() == 2 && self.remaining.len() == 2 {
-            let tiles = vec![self.remaining[0].clone(), self.remaining[1].clone()];
+            let tiles = vec![self.remaining[0], self.remaining[1]];
             if is_ryanmen(&self.remaining) {
                 waits.append(&mut ryanmen_waits(&tiles));
             } else if is_penchan(&self.remaining) {
@@ -55,73 +57,102 @@ impl Division {
 
     fn tiles(&self) -> Vec<Tile> {
         let mut tiles = self.pair.clone();
-        tiles.append(&mut self.melds.iter().flatten().cloned().collect());
+        tiles.append(&mut self.melds.iter().flat_map(|m| m.tiles()).cloned().collect());
         tiles.append(&mut self.remaining.clone());
         tiles
     }
 }
 
-pub(crate) fn divide(tiles: &Vec<Tile>) -> Vec<Division> {
+pub(crate) fn divide(tiles: &[Tile]) -> Vec<Division> {
     let mut results: Vec<Division> = Vec::new();
 
     // Get all candidate pairs (including no pair for tanki)
+    let counts = tile_counts(tiles);
     let mut pairs: Vec<Vec<Tile>> = vec![vec![]];
     for tile in tiles.iter().sorted().dedup() {
-        if tiles.iter().filter(|&t| t == tile).count() >= 2 {
-            pairs.push(vec![tile.clone(), tile.clone()]);
+        if counts[tile.index()] >= 2 {
+            pairs.push(vec![*tile, *tile]);
         }
     }
     for pair in pairs {
-        results.append(&mut divisions_for_pair(&pair, &tiles));
+        results.append(&mut divisions_for_pair(&pair, tiles));
     }
     results
 }
 
-fn divisions_for_pair(pair: &Vec<Tile>, tiles: &[Tile]) -> Vec<Division> {
+fn divisions_for_pair(pair: &[Tile], tiles: &[Tile]) -> Vec<Division> {
     let mut results = vec![];
     // Start by removing the pair
-    let remaining = with_tiles_removed(&tiles, &pair.to_vec());
+    let remaining = with_tiles_removed(tiles, pair);
 
+    // Without a pair, two leftover tiles can never be a valid division
+    if pair.is_empty() && remaining.len() % 3 == 2 {
+        return results;
+    }
     // If there aren't enough tiles to form melds, just return immediately
     if remaining.len() < 3 {
         results.push(Division { pair: pair.to_vec(), melds: vec![], remaining });
         return results;
     }
 
-    // Generate all possible combinations of melds that can be formed with the remaining tiles
-    let n_melds = (remaining.len() / 3) as u8;
-    // We want combinations with replacement since a meld may appear more than once
-    let combinations = combis_with_rep(n_melds, &possible_melds(&remaining));
+    // For each choice of leftover tiles, look up all ways to split the rest into melds
+    let mut counts = tile_counts(&remaining);
+    let mut combinations: Vec<Vec<Vec<Tile>>> = vec![];
+    for leftover in leftover_candidates(&counts, remaining.len() % 3) {
+        leftover.iter().for_each(|&i| counts[i] -= 1);
+        combinations.append(&mut meld_splits(&counts));
+        leftover.iter().for_each(|&i| counts[i] += 1);
+    }
+    // Keep the same order as enumerating combinations of candidate melds
+    combinations.sort_by_cached_key(|combi| meld_order_key(combi));
 
-    for mut combi in combinations {
-        let combi_tiles: Vec<Tile> = combi.iter().map(|m| m.to_vec()).flatten().collect();
-        if !has_tiles(&remaining, &combi_tiles) {
-            continue;
-        }
+    for combi in combinations {
+        let combi_tiles: Vec<Tile> = combi.iter().flatten().cloned().collect();
         let combi_remaining = with_tiles_removed(&remaining, &combi_tiles);
-        if pair.len() == 0 && combi_remaining.len() == 2 {
-            continue;
-        }
-        combi.sort();
-        results.push(Division { pair: pair.to_vec(), melds: combi, remaining: combi_remaining });
+        let melds = combi.into_iter().sorted().map(Meld::concealed).collect();
+        results.push(Division { pair: pair.to_vec(), melds, remaining: combi_remaining });
     }
     results
 }
 
-// Combinations with replacement
-fn combis_with_rep(n: u8, melds: &[Meld]) -> Vec<Vec<Meld>> {
-    if melds.len() == 0 {
-        return vec![];
-    }
-    if n == 0 {
-        return vec![vec![]];
+/// Finds all tiles that complete the hand into melds and a pair.
+///
+/// Unlike `Division::waits`, this considers the hand as a whole and does not divide it.
+pub(crate) fn hand_waits(tiles: &[Tile]) -> Vec<Tile> { completing_tiles(&tile_counts(tiles)) }
+
+/// Checks if the hand consists of melds and a pair.
+pub(crate) fn is_hand_complete(tiles: &[Tile]) -> bool { is_complete(&tile_counts(tiles)) }
+
+// All multisets of `size` tile indices (up to 2) that can be taken from `counts`.
+fn leftover_candidates(counts: &[u8; 34], size: usize) -> Vec<Vec<usize>> {
+    let present: Vec<usize> = (0..34).filter(|&i| counts[i] > 0).collect();
+    match size {
+        0 => vec![vec![]],
+        1 => present.into_iter().map(|i| vec![i]).collect(),
+        2 => present
+            .iter()
+            .flat_map(|&i| present.iter().filter(move |&&j| j >= i).map(move |&j| vec![i, j]))
+            .filter(|leftover| leftover[0] != leftover[1] || counts[leftover[0]] >= 2)
+            .collect(),
+        _ => panic!("Too many leftover tiles"),
     }
-    let mut result: Vec<Vec<Meld>> = combis_with_rep(n, &melds[1..]);
-    for mut subresult in combis_with_rep(n - 1, &melds) {
-        subresult.push(melds[0].clone());
-        result.push(subresult);
+}
+
+// Counts of each meld in a combination, ordered as sequences (by first tile) and then triplets.
+//
+// Sorting by this key reproduces the order of a combinations-with-replacement enumeration over the
+// candidate melds.
+fn meld_order_key(melds: &[Vec<Tile>]) -> [u8; 55] {
+    let mut key = [0; 55];
+    for meld in melds {
+        let first = meld[0].index();
+        if is_sequence(meld) {
+            key[(first / 9) * 7 + first % 9] += 1;
+        } else {
+            key[21 + first] += 1;
+        }
     }
-    result
+    key
 }
 
 #[cfg(test)]
@@ -230,19 +261,16 @@ mod tests {
     }
 
     #[test]
-    fn test_combis_with_rep() {
-        let melds = vec![
-            tiles_from_string("123m"),
-            tiles_from_string("456m"),
-            tiles_from_string("789m"),
-            tiles_from_string("123p"),
-            tiles_from_string("456p"),
-            tiles_from_string("789p"),
-            tiles_from_string("123s"),
-            tiles_from_string("456s"),
-            tiles_from_string("789s"),
-        ];
-        assert_eq!(combis_with_rep(3, &melds).len(), 165);
+    fn test_divide_complete() {
+        assert_eq!(
+            divide(&tiles_from_string("11122233344455m")),
+            vec![
+                d("22m", &["111m", "234m", "345m", "345m"], ""),
+                d("55m", &["111m", "222m", "333m", "444m"], ""),
+                d("55m", &["111m", "234m", "234m", "234m"], ""),
+                d("55m", &["123m", "123m", "123m", "444m"], ""),
+            ]
+        );
     }
 
     fn _test_waits(tiles_str: &str, expected_waits_str: &str) {
@@ -252,12 +280,13 @@ mod tests {
         waits.dedup();
 
         assert_eq!(waits, tiles_from_string(expected_waits_str));
+        assert_eq!(hand_waits(&tiles_from_string(tiles_str)), waits);
     }
 
     fn d(pair: &str, melds: &[&str], remaining: &str) -> Division {
         Division {
             pair: tiles_from_string(pair),
-            melds: melds.into_iter().map(|m| tiles_from_string(m)).collect(),
+            melds: melds.iter().map(|m| Meld::concealed(tiles_from_string(m))).collect(),
             remaining: tiles_from_string(remaining),
         }
     }
@@ -1,14 +1,19 @@
 use crate::{
     division::Division,
-    meld::{is_kanchan, is_penchan, is_shanpon, is_triplet, no_open_calls},
-    tile::Tile,
-    types::{Call, CallType, FuReason, HandContext},
+    meld::{is_kanchan, is_penchan, is_shanpon, no_open_calls},
+    tile::{tile_from_string, Tile},
+    types::{Call, CallType, FuReason, HandContext, Meld, MeldKind, NorthRule, SanmaRules},
 };
 
 pub(crate) fn fu_for_division(
     division: &Division, calls: &[Call], context: &HandContext,
 ) -> Vec<(FuReason, u8)> {
     let mut reasons: Vec<FuReason> = vec![FuReason::Base];
+    // North is always yakuhai under some sanma rules, like the dragons
+    let yakuhai_north = match context.rules.sanma {
+        Some(SanmaRules { north: NorthRule::Yakuhai, .. }) => Some(tile_from_string("4z")),
+        _ => None,
+    };
 
     // Score closed melds
     reasons.append(&mut score_melds(
@@ -17,6 +22,7 @@ pub(crate) fn fu_for_division(
         context.is_tsumo,
         &context.round_wind,
         &context.player_wind,
+        yakuhai_north,
     ));
 
     // Score called melds
@@ -37,6 +43,9 @@ pub(crate) fn fu_for_division(
         if division.remaining[0].is_colour() {
             reasons.push(FuReason::YakuhaiPairColours);
         }
+        if Some(division.remaining[0]) == yakuhai_north {
+            reasons.push(FuReason::YakuhaiPairNorth);
+        }
     }
     if is_kanchan(&division.remaining) {
         reasons.push(FuReason::Kanchan);
@@ -74,12 +83,13 @@ pub(crate) fn fu_for_division(
 
 fn score_melds(
     division: &Division, winning_tile: &Tile, tsumo: bool, round_wind: &Tile, player_wind: &Tile,
+    yakuhai_north: Option<Tile>,
 ) -> Vec<FuReason> {
     let mut reasons = vec![];
     // Score the completed meld
     reasons.append(&mut score_completed_meld(&division.remaining, &winning_tile, tsumo));
     // Score the pair
-    reasons.append(&mut score_pair(&division.pair, &round_wind, &player_wind));
+    reasons.append(&mut score_pair(&division.pair, &round_wind, &player_wind, yakuhai_north));
     // Score closed melds
     for meld in division.melds.iter() {
         reasons.append(&mut score_meld(&meld));
@@ -87,7 +97,9 @@ fn score_melds(
     reasons
 }
 
-fn score_pair(pair: &[Tile], round_wind: &Tile, player_wind: &Tile) -> Vec<FuReason> {
+fn score_pair(
+    pair: &[Tile], round_wind: &Tile, player_wind: &Tile, yakuhai_north: Option<Tile>,
+) -> Vec<FuReason> {
     let mut reasons = vec![];
     if pair.len() != 2 {
         return reasons;
@@ -101,6 +113,9 @@ fn score_pair(pair: &[Tile], round_wind: &Tile, player_wind: &Tile) -> Vec<FuRea
     if &pair[0] == player_wind {
         reasons.push(FuReason::YakuhaiPairPlayerWind);
     }
+    if Some(pair[0]) == yakuhai_north {
+        reasons.push(FuReason::YakuhaiPairNorth);
+    }
     reasons
 }
 
@@ -122,10 +137,10 @@ fn score_completed_meld(remaining: &[Tile], winning_tile: &Tile, tsumo: bool) ->
     }
 }
 
-fn score_meld(meld: &Vec<Tile>) -> Vec<FuReason> {
-    match is_triplet(meld) {
-        true if meld[0].is_honour() => vec![FuReason::ClosedTripletHonours],
-        true if meld[0].is_terminal() => vec![FuReason::ClosedTripletTerminals],
+fn score_meld(meld: &Meld) -> Vec<FuReason> {
+    match meld.kind() == MeldKind::Triplet {
+        true if meld.tile().is_honour() => vec![FuReason::ClosedTripletHonours],
+        true if meld.tile().is_terminal() => vec![FuReason::ClosedTripletTerminals],
         true => vec![FuReason::ClosedTripletSimples],
         _ => vec![],
     }
@@ -168,7 +183,8 @@ fn score_reason(reason: &FuReason) -> u8 {
         // Pair
         FuReason::YakuhaiPairColours
         | FuReason::YakuhaiPairRoundWind
-        | FuReason::YakuhaiPairPlayerWind => 2,
+        | FuReason::YakuhaiPairPlayerWind
+        | FuReason::YakuhaiPairNorth => 2,
         // Triplets
         FuReason::OpenTripletHonours | FuReason::OpenTripletTerminals => 4,
         FuReason::OpenTripletSimples => 2,
@@ -0,0 +1,413 @@
+//! Hand-level checks that don't require a full hand context.
+
+use crate::{
+    division::{divide, hand_waits, is_hand_complete},
+    tile::{tile_counts, tiles_from_string, Tile},
+    types::{Call, Interpretation, Meld},
+};
+use itertools::Itertools;
+
+/// Checks if the hand is complete, i.e. can be interpreted as a winning hand shape.
+///
+/// The winning tile should be included in `tiles`. Yaku are not considered.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     hand::is_agari,
+///     tile::{tile_from_string, tiles_from_string},
+///     types::Call,
+/// };
+///
+/// assert!(is_agari(&tiles_from_string("11122233344455m"), &[]));
+/// assert!(is_agari(&tiles_from_string("113344m55p22s3355z"), &[]));
+/// assert!(is_agari(&tiles_from_string("123m55z"), &vec![Call::pon(tile_from_string("1z")); 3]));
+/// assert!(!is_agari(&tiles_from_string("1234m55z"), &[]));
+/// ```
+pub fn is_agari(tiles: &[Tile], calls: &[Call]) -> bool {
+    if tiles.len() + 3 * calls.len() != 14 {
+        return false;
+    }
+    is_hand_complete(tiles) || (calls.is_empty() && (is_chiitoi(tiles) || is_kokushi(tiles)))
+}
+
+/// Finds all interpretations of a complete hand.
+///
+/// The winning tile should be included in `tiles`. For standard interpretations, the melds formed
+/// from `tiles` come first (in sorted order), followed by the melds from `calls`. Returns nothing
+/// if the hand is not complete.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     hand::decompose,
+///     tile::{tile_from_string, tiles_from_string},
+///     types::{Interpretation, MeldKind},
+/// };
+///
+/// let interpretations = decompose(&tiles_from_string("22334455667788p"), &[]);
+///
+/// assert_eq!(interpretations.len(), 4);
+/// if let Interpretation::Standard { pair, melds } = &interpretations[0] {
+///     assert_eq!(*pair, tile_from_string("2p"));
+///     assert_eq!(melds.len(), 4);
+///     assert_eq!(melds[0].kind(), MeldKind::Sequence);
+///     assert_eq!(melds[0].tiles(), tiles_from_string("345p").as_slice());
+/// } else {
+///     panic!("Expected a standard interpretation");
+/// }
+/// assert_eq!(
+///     interpretations[3],
+///     Interpretation::Chiitoitsu { pairs: tiles_from_string("2345678p") }
+/// );
+/// ```
+pub fn decompose(tiles: &[Tile], calls: &[Call]) -> Vec<Interpretation> {
+    if !is_agari(tiles, calls) {
+        return vec![];
+    }
+    let mut results: Vec<Interpretation> = vec![];
+    if is_hand_complete(tiles) {
+        let called_melds: Vec<Meld> = calls.iter().map(|call| call.meld()).collect();
+        results.extend(divide(tiles).into_iter().map(|division| {
+            let mut melds = division.melds;
+            melds.extend(called_melds.iter().cloned());
+            Interpretation::Standard { pair: division.pair[0], melds }
+        }));
+    }
+    if calls.is_empty() && is_chiitoi(tiles) {
+        let pairs = tiles.iter().sorted().dedup().cloned().collect();
+        results.push(Interpretation::Chiitoitsu { pairs });
+    }
+    if calls.is_empty() && is_kokushi(tiles) {
+        let counts = tile_counts(tiles);
+        let pair = *tiles.iter().find(|t| counts[t.index()] == 2).unwrap();
+        results.push(Interpretation::Kokushi { pair });
+    }
+    results
+}
+
+/// Finds all tiles that would complete the hand, including chiitoitsu and kokushi musou waits.
+///
+/// Tiles that are already held (or called) four times are not counted as waits.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     hand::waits,
+///     tile::{tile_from_string, tiles_from_string},
+///     types::Call,
+/// };
+///
+/// assert_eq!(waits(&tiles_from_string("1113334567888m"), &[]), tiles_from_string("23456789m"));
+/// // The 4-man tanki wait is impossible, since the other three 4-man have been called
+/// assert_eq!(
+///     waits(&tiles_from_string("1234m567p"), &[Call::pon(tile_from_string("4m"))]),
+///     tiles_from_string("1m")
+/// );
+/// ```
+pub fn waits(tiles: &[Tile], calls: &[Call]) -> Vec<Tile> {
+    let mut waits = hand_waits(tiles);
+    if calls.is_empty() {
+        waits.append(&mut chiitoi_waits(tiles));
+        waits.append(&mut kokushi_waits(tiles));
+    }
+
+    // Reject impossible waits, counting called tiles too
+    let mut all_tiles = tiles.to_vec();
+    all_tiles.extend(calls.iter().flat_map(|call| call.tiles()));
+    let counts = tile_counts(&all_tiles);
+    waits.retain(|w| counts[w.index()] < 4);
+
+    waits.sort();
+    waits.dedup();
+    waits
+}
+
+/// Checks if the hand is one tile away from completion.
+pub fn is_tenpai(tiles: &[Tile], calls: &[Call]) -> bool { !waits(tiles, calls).is_empty() }
+
+/// Finds the number of tiles the hand needs to exchange to reach tenpai, i.e. the shanten number.
+///
+/// Returns 0 for a tenpai hand and -1 for a complete hand. `tiles` may be 13 tiles (less calls)
+/// or 14 with a drawn tile. Chiitoitsu and kokushi musou are considered for closed hands.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{hand::shanten, tile::tiles_from_string};
+///
+/// assert_eq!(shanten(&tiles_from_string("123m456p789s1234z"), &[]), 2);
+/// assert_eq!(shanten(&tiles_from_string("123m456p789s1122z"), &[]), 0);
+/// assert_eq!(shanten(&tiles_from_string("123m456p789s11222z"), &[]), -1);
+/// // Chiitoitsu
+/// assert_eq!(shanten(&tiles_from_string("1144m2299p338s15z"), &[]), 1);
+/// ```
+pub fn shanten(tiles: &[Tile], calls: &[Call]) -> i8 {
+    let counts = tile_counts(tiles);
+    let standard = standard_shanten(&counts, calls.len());
+    if !calls.is_empty() {
+        return standard;
+    }
+    standard.min(chiitoi_shanten(&counts)).min(kokushi_shanten(&counts))
+}
+
+/// Checks if a discard pond qualifies for nagashi mangan at an exhaustive draw.
+///
+/// Every discard must be a terminal or honour, and none of them may have been called by another
+/// player.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{hand::is_nagashi_mangan, tile::tiles_from_string};
+///
+/// assert!(is_nagashi_mangan(&tiles_from_string("19m9p1s1234567z"), false));
+/// assert!(!is_nagashi_mangan(&tiles_from_string("19m9p1s1234567z"), true));
+/// assert!(!is_nagashi_mangan(&tiles_from_string("19m8p1s1234567z"), false));
+/// ```
+pub fn is_nagashi_mangan(discards: &[Tile], discards_called: bool) -> bool {
+    !discards.is_empty()
+        && !discards_called
+        && discards.iter().all(|t| t.is_terminal() || t.is_honour())
+}
+
+// 8 - 2 * melds - partial melds - pair, counting at most 4 melds and partial melds together
+fn standard_shanten(counts: &[u8; 34], n_calls: usize) -> i8 {
+    let n_blocks = 4 - n_calls.min(4) as i8;
+    let mut counts = *counts;
+    let mut best = blocks_value(&mut counts, 0, 0, 0, n_blocks);
+    for i in 0..34 {
+        if counts[i] >= 2 {
+            counts[i] -= 2;
+            best = best.max(blocks_value(&mut counts, 0, 0, 0, n_blocks) + 1);
+            counts[i] += 2;
+        }
+    }
+    2 * n_blocks - best
+}
+
+// Finds the best 2 * melds + partial melds over all ways to split the tiles from `start` on
+fn blocks_value(counts: &mut [u8; 34], start: usize, melds: i8, partials: i8, n_blocks: i8) -> i8 {
+    let i = match (start..34).find(|&i| counts[i] > 0) {
+        Some(i) => i,
+        None => return 2 * melds + partials.min(n_blocks - melds),
+    };
+    let is_number = i < 27;
+    let mut groups: Vec<Vec<usize>> = vec![];
+    if counts[i] >= 3 {
+        groups.push(vec![i, i, i]);
+    }
+    if is_number && i % 9 <= 6 && counts[i + 1] > 0 && counts[i + 2] > 0 {
+        groups.push(vec![i, i + 1, i + 2]);
+    }
+    if counts[i] >= 2 {
+        groups.push(vec![i, i]);
+    }
+    if is_number && i % 9 <= 7 && counts[i + 1] > 0 {
+        groups.push(vec![i, i + 1]);
+    }
+    if is_number && i % 9 <= 6 && counts[i + 2] > 0 {
+        groups.push(vec![i, i + 2]);
+    }
+
+    // Leave one copy of the tile unused
+    counts[i] -= 1;
+    let mut best = blocks_value(counts, i, melds, partials, n_blocks);
+    counts[i] += 1;
+    for group in groups {
+        let (melds, partials) =
+            if group.len() == 3 { (melds + 1, partials) } else { (melds, partials + 1) };
+        if melds > n_blocks {
+            continue;
+        }
+        group.iter().for_each(|&j| counts[j] -= 1);
+        best = best.max(blocks_value(counts, i, melds, partials, n_blocks));
+        group.iter().for_each(|&j| counts[j] += 1);
+    }
+    best
+}
+
+// Pairs of the same tile don't count twice
+fn chiitoi_shanten(counts: &[u8; 34]) -> i8 {
+    let n_pairs = counts.iter().filter(|&&c| c >= 2).count() as i8;
+    let n_kinds = counts.iter().filter(|&&c| c > 0).count() as i8;
+    6 - n_pairs + (7 - n_kinds).max(0)
+}
+
+fn kokushi_shanten(counts: &[u8; 34]) -> i8 {
+    let terminals_honours: Vec<usize> =
+        tiles_from_string("19m19p19s1234567z").iter().map(|t| t.index()).collect();
+    let n_kinds = terminals_honours.iter().filter(|&&i| counts[i] > 0).count() as i8;
+    let has_pair = terminals_honours.iter().any(|&i| counts[i] >= 2);
+    13 - n_kinds - has_pair as i8
+}
+
+fn is_chiitoi(tiles: &[Tile]) -> bool {
+    let counts = tile_counts(tiles);
+    tiles.len() == 14 && tiles.iter().all(|t| counts[t.index()] == 2)
+}
+
+fn is_kokushi(tiles: &[Tile]) -> bool {
+    tiles.len() == 14 && kokushi_waits(&tiles[1..]).contains(&tiles[0])
+}
+
+fn chiitoi_waits(tiles: &[Tile]) -> Vec<Tile> {
+    let counts = tile_counts(tiles);
+    let singles: Vec<Tile> = tiles.iter().filter(|t| counts[t.index()] == 1).cloned().collect();
+    let n_pairs = counts.iter().filter(|&&c| c == 2).count();
+    if tiles.len() == 13 && n_pairs == 6 && singles.len() == 1 {
+        singles
+    } else {
+        vec![]
+    }
+}
+
+fn kokushi_waits(tiles: &[Tile]) -> Vec<Tile> {
+    let terminals_honours = tiles_from_string("19m19p19s1234567z");
+    if tiles.len() != 13 || tiles.iter().any(|t| !terminals_honours.contains(t)) {
+        return vec![];
+    }
+    let counts = tile_counts(tiles);
+    let missing: Vec<Tile> =
+        terminals_honours.iter().filter(|t| counts[t.index()] == 0).cloned().collect();
+    match missing.len() {
+        // 13-sided wait
+        0 => terminals_honours,
+        // The hand has a pair, so it is only waiting on the missing tile
+        1 => missing,
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::tile_from_string;
+
+    #[test]
+    fn test_waits_standard() {
+        assert_eq!(waits(&ts("1112345678999m"), &[]), ts("123456789m"));
+        assert_eq!(waits(&ts("4555m123789s333z"), &[]), ts("346m"));
+        assert!(waits(&ts("1357m2468p1357s9m"), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_waits_chiitoi() {
+        assert_eq!(waits(&ts("113344m55p22s335z"), &[]), ts("5z"));
+        // Chiitoitsu and standard waits together
+        assert_eq!(waits(&ts("11223344m55667p"), &[]), ts("47p"));
+        // Four of a kind is not two pairs
+        assert!(waits(&ts("1111m3344p55667s"), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_waits_kokushi() {
+        assert_eq!(waits(&ts("19m19p19s1234567z"), &[]), ts("19m19p19s1234567z"));
+        assert_eq!(waits(&ts("19m19p19s1234566z"), &[]), ts("7z"));
+    }
+
+    #[test]
+    fn test_waits_with_calls() {
+        // The last 4-man has been called
+        assert_eq!(waits(&ts("1234m567p"), &[Call::pon(tile_from_string("4m"))]), ts("1m"));
+        // No chiitoitsu with calls
+        assert!(waits(&ts("1133m4455p7s"), &[Call::ankan(tile_from_string("1z"))]).is_empty());
+    }
+
+    #[test]
+    fn test_is_agari() {
+        assert!(is_agari(&ts("234456m66p123444s"), &[]));
+        assert!(is_agari(&ts("19m19p19s12345677z"), &[]));
+        assert!(is_agari(&ts("77z"), &vec![Call::ankan(tile_from_string("1z")); 4]));
+        // Wrong number of tiles
+        assert!(!is_agari(&ts("234456m66p12344s"), &[]));
+        assert!(!is_agari(&ts("234456m66p123444s"), &[Call::pon(tile_from_string("1z"))]));
+        // No chiitoitsu or kokushi with calls
+        assert!(!is_agari(&ts("113344m55p22s"), &[Call::chi(tile_from_string("1s"))]));
+    }
+
+    #[test]
+    fn test_decompose_standard() {
+        assert_eq!(
+            decompose(&ts("123m55z"), &vec![Call::pon(tile_from_string("1z")); 3]),
+            vec![Interpretation::Standard {
+                pair: tile_from_string("5z"),
+                melds: vec![
+                    Meld::concealed(ts("123m")),
+                    Call::pon(tile_from_string("1z")).meld(),
+                    Call::pon(tile_from_string("1z")).meld(),
+                    Call::pon(tile_from_string("1z")).meld(),
+                ],
+            }]
+        );
+        assert_eq!(decompose(&ts("11122233344455m"), &[]).len(), 4);
+        assert!(decompose(&ts("1234m55z"), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_decompose_four_quads() {
+        // Hadaka tanki: only the pair is left in the hand
+        let calls = vec![Call::ankan(tile_from_string("1z")); 4];
+        assert_eq!(
+            decompose(&ts("77z"), &calls),
+            vec![Interpretation::Standard {
+                pair: tile_from_string("7z"),
+                melds: calls.iter().map(|call| call.meld()).collect(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_decompose_chiitoi() {
+        assert_eq!(
+            decompose(&ts("113344m55p22s3355z"), &[]),
+            vec![Interpretation::Chiitoitsu { pairs: ts("134m5p2s35z") }]
+        );
+        // Ryanpeikou shape is also chiitoitsu
+        assert_eq!(decompose(&ts("223344m556677p11z"), &[]).len(), 2);
+    }
+
+    #[test]
+    fn test_decompose_kokushi() {
+        assert_eq!(
+            decompose(&ts("19m19p19s12345677z"), &[]),
+            vec![Interpretation::Kokushi { pair: tile_from_string("7z") }]
+        );
+    }
+
+    #[test]
+    fn test_is_tenpai() {
+        assert!(is_tenpai(&ts("4555m123789s333z"), &[]));
+        assert!(!is_tenpai(&ts("4559m123789s333z"), &[]));
+    }
+
+    #[test]
+    fn test_shanten_standard() {
+        assert_eq!(shanten(&ts("11123456789995m"), &[]), -1);
+        assert_eq!(shanten(&ts("1112345678999m"), &[]), 0);
+        assert_eq!(shanten(&ts("147m258p369s1234z"), &[]), 6);
+        assert_eq!(shanten(&ts("12m45p78s1z"), &vec![Call::pon(tile_from_string("5z")); 3]), 1);
+        assert_eq!(shanten(&ts("4559m123789s333z"), &[]), 1);
+    }
+
+    #[test]
+    fn test_shanten_irregular() {
+        assert_eq!(shanten(&ts("113344m55p22s335z"), &[]), 0);
+        // Four of a kind counts as one pair
+        assert_eq!(shanten(&ts("1111m3344p55667s"), &[]), 1);
+        assert_eq!(shanten(&ts("19m19p19s1234566z"), &[]), 0);
+        assert_eq!(shanten(&ts("19m19p19s123456z5m"), &[]), 1);
+    }
+
+    #[test]
+    fn test_is_nagashi_mangan() {
+        assert!(is_nagashi_mangan(&ts("1z"), false));
+        assert!(!is_nagashi_mangan(&[], false));
+        assert!(!is_nagashi_mangan(&ts("19m5z5m"), false));
+    }
+
+    fn ts(tiles: &str) -> Vec<Tile> { tiles_from_string(tiles) }
+}
@@ -1 +1,348 @@
+//! A validating builder for `HandContext`.
 
+use crate::{
+    tile::{tile_from_string, Tile},
+    types::{HandContext, Rules},
+};
+use std::{error, fmt};
+
+/// Represents an inconsistent combination of `HandContext` flags.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContextError {
+    /// Haitei (last tile from the wall) cannot also be a rinshan (replacement) draw.
+    HaiteiWithRinshan,
+    /// Houtei (last discard) cannot also be a chankan (robbing a quad), since a quad is not a
+    /// discard.
+    HouteiWithChankan,
+    /// Ippatsu requires riichi or double riichi.
+    IppatsuWithoutRiichi,
+    /// Open riichi requires riichi or double riichi.
+    OpenRiichiWithoutRiichi,
+    /// Double riichi replaces riichi, so only one should be set.
+    DoubleRiichiWithRiichi,
+    /// Tenhou is only possible for the dealer.
+    TenhouNonDealer,
+    /// Tenhou is only possible by tsumo.
+    TenhouRon,
+    /// Chiihou is only possible for a nondealer.
+    ChiihouDealer,
+    /// Chiihou is only possible by tsumo.
+    ChiihouRon,
+    /// Renhou is only possible for a nondealer.
+    RenhouDealer,
+    /// Renhou is only possible by ron.
+    RenhouTsumo,
+    /// Haitei is a win by tsumo on the last tile; use houtei for ron.
+    HaiteiRon,
+    /// Houtei is a win by ron on the last discard; use haitei for tsumo.
+    HouteiTsumo,
+    /// Rinshan is a win by tsumo on a replacement tile.
+    RinshanRon,
+    /// Chankan is a win by ron on a tile added to a quad.
+    ChankanTsumo,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            ContextError::HaiteiWithRinshan => "haitei and rinshan cannot both be set",
+            ContextError::HouteiWithChankan => "houtei and chankan cannot both be set",
+            ContextError::IppatsuWithoutRiichi => "ippatsu requires riichi or double riichi",
+            ContextError::OpenRiichiWithoutRiichi => "open riichi requires riichi or double riichi",
+            ContextError::DoubleRiichiWithRiichi => "double riichi and riichi cannot both be set",
+            ContextError::TenhouNonDealer => "tenhou requires the dealer (east seat)",
+            ContextError::TenhouRon => "tenhou requires a tsumo win",
+            ContextError::ChiihouDealer => "chiihou requires a nondealer",
+            ContextError::ChiihouRon => "chiihou requires a tsumo win",
+            ContextError::RenhouDealer => "renhou requires a nondealer",
+            ContextError::RenhouTsumo => "renhou requires a ron win",
+            ContextError::HaiteiRon => "haitei requires a tsumo win",
+            ContextError::HouteiTsumo => "houtei requires a ron win",
+            ContextError::RinshanRon => "rinshan requires a tsumo win",
+            ContextError::ChankanTsumo => "chankan requires a ron win",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl error::Error for ContextError {}
+
+impl HandContext {
+    /// Starts building a context for a win on `winning_tile`.
+    pub fn builder(winning_tile: Tile) -> HandContextBuilder {
+        HandContextBuilder::new(winning_tile)
+    }
+
+    /// Checks that the flags are consistent with each other, returning the first problem found.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::{hand_context::ContextError, tile::tile_from_string, types::HandContext};
+    ///
+    /// let context = HandContext { is_ippatsu: true, ..Default::default() };
+    /// assert_eq!(context.validate(), Err(ContextError::IppatsuWithoutRiichi));
+    /// ```
+    pub fn validate(&self) -> Result<(), ContextError> {
+        if self.is_haitei && self.is_rinshan {
+            return Err(ContextError::HaiteiWithRinshan);
+        }
+        if self.is_houtei && self.is_chankan {
+            return Err(ContextError::HouteiWithChankan);
+        }
+        if self.is_double_riichi && self.is_riichi {
+            return Err(ContextError::DoubleRiichiWithRiichi);
+        }
+        let riichi = self.is_riichi || self.is_double_riichi;
+        if self.is_ippatsu && !riichi {
+            return Err(ContextError::IppatsuWithoutRiichi);
+        }
+        if self.is_open_riichi && !riichi {
+            return Err(ContextError::OpenRiichiWithoutRiichi);
+        }
+        let is_dealer = self.player_wind == tile_from_string("1z");
+        let checks = [
+            (self.is_tenhou && !is_dealer, ContextError::TenhouNonDealer),
+            (self.is_tenhou && !self.is_tsumo, ContextError::TenhouRon),
+            (self.is_chiihou && is_dealer, ContextError::ChiihouDealer),
+            (self.is_chiihou && !self.is_tsumo, ContextError::ChiihouRon),
+            (self.is_renhou && is_dealer, ContextError::RenhouDealer),
+            (self.is_renhou && self.is_tsumo, ContextError::RenhouTsumo),
+            (self.is_haitei && !self.is_tsumo, ContextError::HaiteiRon),
+            (self.is_houtei && self.is_tsumo, ContextError::HouteiTsumo),
+            (self.is_rinshan && !self.is_tsumo, ContextError::RinshanRon),
+            (self.is_chankan && self.is_tsumo, ContextError::ChankanTsumo),
+        ];
+        match checks.iter().find(|(failed, _)| *failed) {
+            Some(&(_, error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Builds a `HandContext`, checking that the flags are consistent.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{hand_context::ContextError, tile::tile_from_string, types::HandContext};
+///
+/// let context = HandContext::builder(tile_from_string("4s"))
+///     .tsumo()
+///     .player_wind(tile_from_string("2z"))
+///     .riichi()
+///     .ippatsu()
+///     .dora(1)
+///     .build()
+///     .unwrap();
+/// assert!(context.is_riichi && context.is_ippatsu);
+///
+/// let result = HandContext::builder(tile_from_string("4s")).haitei().rinshan().build();
+/// assert_eq!(result, Err(ContextError::HaiteiWithRinshan));
+/// ```
+#[derive(Clone, Debug)]
+pub struct HandContextBuilder {
+    context: HandContext,
+}
+
+impl HandContextBuilder {
+    /// Starts with a ron by the dealer in an east round, with no other flags set.
+    pub fn new(winning_tile: Tile) -> HandContextBuilder {
+        HandContextBuilder { context: HandContext { winning_tile, ..Default::default() } }
+    }
+
+    pub fn tsumo(mut self) -> Self {
+        self.context.is_tsumo = true;
+        self
+    }
+
+    pub fn round_wind(mut self, wind: Tile) -> Self {
+        self.context.round_wind = wind;
+        self
+    }
+
+    pub fn player_wind(mut self, wind: Tile) -> Self {
+        self.context.player_wind = wind;
+        self
+    }
+
+    pub fn riichi(mut self) -> Self {
+        self.context.is_riichi = true;
+        self
+    }
+
+    pub fn double_riichi(mut self) -> Self {
+        self.context.is_double_riichi = true;
+        self
+    }
+
+    pub fn ippatsu(mut self) -> Self {
+        self.context.is_ippatsu = true;
+        self
+    }
+
+    pub fn open_riichi(mut self) -> Self {
+        self.context.is_open_riichi = true;
+        self
+    }
+
+    pub fn haitei(mut self) -> Self {
+        self.context.is_haitei = true;
+        self
+    }
+
+    pub fn houtei(mut self) -> Self {
+        self.context.is_houtei = true;
+        self
+    }
+
+    pub fn rinshan(mut self) -> Self {
+        self.context.is_rinshan = true;
+        self
+    }
+
+    pub fn chankan(mut self) -> Self {
+        self.context.is_chankan = true;
+        self
+    }
+
+    pub fn tenhou(mut self) -> Self {
+        self.context.is_tenhou = true;
+        self
+    }
+
+    pub fn chiihou(mut self) -> Self {
+        self.context.is_chiihou = true;
+        self
+    }
+
+    pub fn renhou(mut self) -> Self {
+        self.context.is_renhou = true;
+        self
+    }
+
+    pub fn non_riichi_deal_in(mut self) -> Self {
+        self.context.is_non_riichi_deal_in = true;
+        self
+    }
+
+    pub fn dora(mut self, n: u8) -> Self {
+        self.context.n_dora = n;
+        self
+    }
+
+    pub fn akadora(mut self, n: u8) -> Self {
+        self.context.n_akadora = n;
+        self
+    }
+
+    pub fn uradora(mut self, n: u8) -> Self {
+        self.context.n_uradora = n;
+        self
+    }
+
+    pub fn nukidora(mut self, n: u8) -> Self {
+        self.context.n_nukidora = n;
+        self
+    }
+
+    pub fn dora_indicators(mut self, indicators: Vec<Tile>) -> Self {
+        self.context.dora_indicators = indicators;
+        self
+    }
+
+    pub fn uradora_indicators(mut self, indicators: Vec<Tile>) -> Self {
+        self.context.uradora_indicators = indicators;
+        self
+    }
+
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.context.rules = rules;
+        self
+    }
+
+    /// Returns the context, or the first inconsistency found (see `HandContext::validate`).
+    pub fn build(self) -> Result<HandContext, ContextError> {
+        self.context.validate()?;
+        Ok(self.context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> HandContextBuilder { HandContext::builder(tile_from_string("4s")) }
+
+    #[test]
+    fn test_build() {
+        let context = builder()
+            .tsumo()
+            .round_wind(tile_from_string("2z"))
+            .player_wind(tile_from_string("3z"))
+            .double_riichi()
+            .ippatsu()
+            .uradora(2)
+            .build()
+            .unwrap();
+        let expected = HandContext {
+            winning_tile: tile_from_string("4s"),
+            is_tsumo: true,
+            round_wind: tile_from_string("2z"),
+            player_wind: tile_from_string("3z"),
+            is_double_riichi: true,
+            is_ippatsu: true,
+            n_uradora: 2,
+            ..Default::default()
+        };
+        assert_eq!(context, expected);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        assert_eq!(
+            builder().haitei().rinshan().tsumo().build(),
+            Err(ContextError::HaiteiWithRinshan)
+        );
+        assert_eq!(builder().ippatsu().build(), Err(ContextError::IppatsuWithoutRiichi));
+        assert_eq!(builder().open_riichi().build(), Err(ContextError::OpenRiichiWithoutRiichi));
+        assert_eq!(
+            builder().riichi().double_riichi().build(),
+            Err(ContextError::DoubleRiichiWithRiichi)
+        );
+        assert_eq!(
+            builder().tenhou().tsumo().player_wind(tile_from_string("2z")).build(),
+            Err(ContextError::TenhouNonDealer)
+        );
+        assert_eq!(builder().tenhou().build(), Err(ContextError::TenhouRon));
+        assert!(builder().tenhou().tsumo().build().is_ok());
+    }
+
+    #[test]
+    fn test_build_errors_win_type() {
+        let nondealer = || builder().player_wind(tile_from_string("2z"));
+        assert_eq!(builder().chiihou().tsumo().build(), Err(ContextError::ChiihouDealer));
+        assert_eq!(nondealer().chiihou().build(), Err(ContextError::ChiihouRon));
+        assert!(nondealer().chiihou().tsumo().build().is_ok());
+        assert_eq!(builder().renhou().build(), Err(ContextError::RenhouDealer));
+        assert_eq!(nondealer().renhou().tsumo().build(), Err(ContextError::RenhouTsumo));
+        assert!(nondealer().renhou().build().is_ok());
+
+        assert_eq!(builder().haitei().build(), Err(ContextError::HaiteiRon));
+        assert_eq!(builder().houtei().tsumo().build(), Err(ContextError::HouteiTsumo));
+        assert_eq!(builder().rinshan().build(), Err(ContextError::RinshanRon));
+        assert_eq!(builder().chankan().tsumo().build(), Err(ContextError::ChankanTsumo));
+        assert!(builder().haitei().tsumo().build().is_ok());
+        assert!(builder().houtei().build().is_ok());
+        assert!(builder().chankan().build().is_ok());
+        assert_eq!(builder().houtei().chankan().build(), Err(ContextError::HouteiWithChankan));
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            ContextError::TenhouNonDealer.to_string(),
+            "tenhou requires the dealer (east seat)"
+        );
+    }
+}
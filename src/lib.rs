@@ -1,13 +1,24 @@
 //! A riichi mahjong library.
 
+pub mod catalog;
+pub mod custom_yaku;
+pub mod diagnosis;
+pub mod hand;
+pub mod hand_context;
 pub mod player;
+pub mod riichi;
 pub mod score;
+pub mod settlement;
 pub mod tile;
 pub mod types;
+pub mod wall;
 
+mod breakdown;
 mod calculation;
 mod division;
 mod fu;
+mod locale;
 mod meld;
+mod suit_table;
 mod utils;
 mod yaku;
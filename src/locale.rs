@@ -0,0 +1,352 @@
+//! Names of yaku, fu reasons, waits and limits in each locale.
+
+use crate::types::{FuReason, HanReason, Limit, Locale, Wait, Yaku, Yakuman};
+
+impl HanReason {
+    /// The name of the yaku or yakuman in the given locale.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::types::{HanReason, Locale, Yaku};
+    ///
+    /// let tanyao = HanReason::Yaku(Yaku::Tanyao);
+    /// assert_eq!(tanyao.name(Locale::Romaji), "Tanyao");
+    /// assert_eq!(tanyao.name(Locale::English), "All simples");
+    /// assert_eq!(tanyao.name(Locale::Japanese), "断幺九");
+    /// ```
+    pub fn name(&self, locale: Locale) -> &'static str {
+        match self {
+            HanReason::Yaku(yaku) => select(yaku_names(*yaku), locale),
+            HanReason::Yakuman(yakuman) => select(yakuman_names(*yakuman), locale),
+            // User-defined yaku have a single name
+            HanReason::CustomYaku(name) | HanReason::CustomYakuman(name) => name,
+        }
+    }
+}
+
+impl FuReason {
+    /// The name of the fu reason in the given locale.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::types::{FuReason, Locale};
+    ///
+    /// assert_eq!(FuReason::ClosedTripletHonours.name(Locale::Romaji), "Ankou (jihai)");
+    /// assert_eq!(FuReason::ClosedTripletHonours.name(Locale::English), "Closed triplet (honours)");
+    /// assert_eq!(FuReason::ClosedTripletHonours.name(Locale::Japanese), "暗刻（字牌）");
+    /// ```
+    pub fn name(&self, locale: Locale) -> &'static str { select(fu_reason_names(*self), locale) }
+}
+
+impl Wait {
+    /// The name of the wait in the given locale.
+    pub fn name(&self, locale: Locale) -> &'static str { select(wait_names(*self), locale) }
+}
+
+impl Limit {
+    /// The name of the limit in the given locale.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::types::{Limit, Locale};
+    ///
+    /// assert_eq!(Limit::Haneman.name(Locale::Japanese), "跳満");
+    /// ```
+    pub fn name(&self, locale: Locale) -> &'static str { select(limit_names(*self), locale) }
+}
+
+fn select(names: [&'static str; 3], locale: Locale) -> &'static str {
+    let [romaji, english, japanese] = names;
+    match locale {
+        Locale::Romaji => romaji,
+        Locale::English => english,
+        Locale::Japanese => japanese,
+    }
+}
+
+// [romaji, English, Japanese]
+
+fn yaku_names(yaku: Yaku) -> [&'static str; 3] {
+    match yaku {
+        Yaku::MenzenTsumo => ["Menzen tsumo", "Fully concealed self-draw", "門前清自摸和"],
+        Yaku::Riichi => ["Riichi", "Ready hand", "立直"],
+        Yaku::Ippatsu => ["Ippatsu", "One shot", "一発"],
+        Yaku::Pinfu => ["Pinfu", "All sequences", "平和"],
+        Yaku::Iipeikou => ["Iipeikou", "Pure double sequence", "一盃口"],
+        Yaku::HaiteiRaoyue => ["Haitei raoyue", "Under the sea", "海底摸月"],
+        Yaku::HouteiRaoyui => ["Houtei raoyui", "Under the river", "河底撈魚"],
+        Yaku::RinshanKaihou => ["Rinshan kaihou", "After a quad", "嶺上開花"],
+        Yaku::Chankan => ["Chankan", "Robbing a quad", "搶槓"],
+        Yaku::Tanyao => ["Tanyao", "All simples", "断幺九"],
+        Yaku::Ton => ["Ton", "East wind", "東"],
+        Yaku::Nan => ["Nan", "South wind", "南"],
+        Yaku::Sha => ["Sha", "West wind", "西"],
+        Yaku::Pei => ["Pei", "North wind", "北"],
+        Yaku::Haku => ["Haku", "White dragon", "白"],
+        Yaku::Hatsu => ["Hatsu", "Green dragon", "發"],
+        Yaku::Chun => ["Chun", "Red dragon", "中"],
+        Yaku::DoubleRiichi => ["Double riichi", "Double ready hand", "ダブル立直"],
+        Yaku::Chantaiyao => ["Chanta", "Half outside hand", "混全帯幺九"],
+        Yaku::SanshokuDoujun => ["Sanshoku doujun", "Mixed triple sequence", "三色同順"],
+        Yaku::Sankantsu => ["Sankantsu", "Three quads", "三槓子"],
+        Yaku::Ittsu => ["Ittsu", "Pure straight", "一気通貫"],
+        Yaku::Toitoi => ["Toitoi", "All triplets", "対々和"],
+        Yaku::Sanankou => ["Sanankou", "Three concealed triplets", "三暗刻"],
+        Yaku::SanshokuDoukou => ["Sanshoku doukou", "Triple triplets", "三色同刻"],
+        Yaku::Chiitoitsu => ["Chiitoitsu", "Seven pairs", "七対子"],
+        Yaku::Honroutou => ["Honroutou", "All terminals and honours", "混老頭"],
+        Yaku::Shousangen => ["Shousangen", "Little three dragons", "小三元"],
+        Yaku::Ryanpeikou => ["Ryanpeikou", "Twice pure double sequence", "二盃口"],
+        Yaku::Honitsu => ["Honitsu", "Half flush", "混一色"],
+        Yaku::JunchanTaiyao => ["Junchan", "Fully outside hand", "純全帯幺九"],
+        Yaku::Chinitsu => ["Chinitsu", "Full flush", "清一色"],
+        Yaku::Renhou => ["Renhou", "Blessing of man", "人和"],
+        Yaku::Sanrenkou => ["Sanrenkou", "Three consecutive triplets", "三連刻"],
+        Yaku::OpenRiichi => ["Open riichi", "Open ready hand", "オープン立直"],
+        Yaku::Dora => ["Dora", "Bonus tile", "ドラ"],
+        Yaku::Akadora => ["Akadora", "Red five", "赤ドラ"],
+        Yaku::Uradora => ["Uradora", "Hidden bonus tile", "裏ドラ"],
+        Yaku::Nukidora => ["Nukidora", "North bonus tile", "抜きドラ"],
+    }
+}
+
+fn yakuman_names(yakuman: Yakuman) -> [&'static str; 3] {
+    match yakuman {
+        Yakuman::KazoeYakuman => ["Kazoe yakuman", "Counted yakuman", "数え役満"],
+        Yakuman::KokushiMusou => ["Kokushi musou", "Thirteen orphans", "国士無双"],
+        Yakuman::Suuankou => ["Suuankou", "Four concealed triplets", "四暗刻"],
+        Yakuman::Daisangen => ["Daisangen", "Big three dragons", "大三元"],
+        Yakuman::Shousuushii => ["Shousuushii", "Little four winds", "小四喜"],
+        Yakuman::Daisuushii => ["Daisuushii", "Big four winds", "大四喜"],
+        Yakuman::Tsuuiisou => ["Tsuuiisou", "All honours", "字一色"],
+        Yakuman::Chinroutou => ["Chinroutou", "All terminals", "清老頭"],
+        Yakuman::Ryuuiisou => ["Ryuuiisou", "All green", "緑一色"],
+        Yakuman::ChuurenPoutou => ["Chuuren poutou", "Nine gates", "九蓮宝燈"],
+        Yakuman::Suukantsu => ["Suukantsu", "Four quads", "四槓子"],
+        Yakuman::Tenhou => ["Tenhou", "Blessing of heaven", "天和"],
+        Yakuman::Chiihou => ["Chiihou", "Blessing of earth", "地和"],
+        Yakuman::NagashiMangan => ["Nagashi mangan", "Mangan at draw", "流し満貫"],
+        Yakuman::OpenRiichiDealIn => {
+            ["Open riichi deal-in", "Dealing into an open ready hand", "オープン立直放銃"]
+        }
+        Yakuman::Daisharin => ["Daisharin", "Big wheels", "大車輪"],
+        Yakuman::IisouSuushun => ["Iisou suushun", "Pure quadruple sequence", "一色四順"],
+        Yakuman::Shiisanpuutaa => ["Shiisanpuutaa", "Thirteen unconnected tiles", "十三不塔"],
+        Yakuman::Daichisei => ["Daichisei", "Big seven stars", "大七星"],
+        Yakuman::Ishinouenimosannen => {
+            ["Ishino uenimo sannen", "Three years on a stone", "石の上にも三年"]
+        }
+    }
+}
+
+fn fu_reason_names(fu_reason: FuReason) -> [&'static str; 3] {
+    match fu_reason {
+        FuReason::Base => ["Fuutei", "Base", "副底"],
+        FuReason::Chiitoitsu => ["Chiitoitsu", "Chiitoitsu", "七対子"],
+        FuReason::ClosedRon => ["Menzen kafu", "Closed ron", "門前加符"],
+        FuReason::TsumoNoPinfu => ["Tsumo fu", "Tsumo, no pinfu", "ツモ符"],
+        FuReason::OpenPinfu => ["Kuipinfu", "Open pinfu", "喰い平和"],
+        FuReason::YakuhaiPairColours => {
+            ["Sangenpai jantou", "Yakuhai pair (colours)", "三元牌の雀頭"]
+        }
+        FuReason::YakuhaiPairRoundWind => {
+            ["Bakaze jantou", "Yakuhai pair (round wind)", "場風の雀頭"]
+        }
+        FuReason::YakuhaiPairPlayerWind => {
+            ["Jikaze jantou", "Yakuhai pair (player wind)", "自風の雀頭"]
+        }
+        FuReason::OpenTripletHonours => {
+            ["Minkou (jihai)", "Open triplet (honours)", "明刻（字牌）"]
+        }
+        FuReason::OpenTripletTerminals => {
+            ["Minkou (routouhai)", "Open triplet (terminals)", "明刻（老頭牌）"]
+        }
+        FuReason::OpenTripletSimples => {
+            ["Minkou (chunchanpai)", "Open triplet (simples)", "明刻（中張牌）"]
+        }
+        FuReason::ClosedTripletHonours => {
+            ["Ankou (jihai)", "Closed triplet (honours)", "暗刻（字牌）"]
+        }
+        FuReason::ClosedTripletTerminals => {
+            ["Ankou (routouhai)", "Closed triplet (terminals)", "暗刻（老頭牌）"]
+        }
+        FuReason::ClosedTripletSimples => {
+            ["Ankou (chunchanpai)", "Closed triplet (simples)", "暗刻（中張牌）"]
+        }
+        FuReason::OpenQuadHonours => ["Minkan (jihai)", "Open quad (honours)", "明槓（字牌）"],
+        FuReason::OpenQuadTerminals => {
+            ["Minkan (routouhai)", "Open quad (terminals)", "明槓（老頭牌）"]
+        }
+        FuReason::OpenQuadSimples => {
+            ["Minkan (chunchanpai)", "Open quad (simples)", "明槓（中張牌）"]
+        }
+        FuReason::ClosedQuadHonours => ["Ankan (jihai)", "Closed quad (honours)", "暗槓（字牌）"],
+        FuReason::ClosedQuadTerminals => {
+            ["Ankan (routouhai)", "Closed quad (terminals)", "暗槓（老頭牌）"]
+        }
+        FuReason::ClosedQuadSimples => {
+            ["Ankan (chunchanpai)", "Closed quad (simples)", "暗槓（中張牌）"]
+        }
+        FuReason::Kanchan => ["Kanchan machi", "Kanchan wait", "嵌張待ち"],
+        FuReason::Penchan => ["Penchan machi", "Penchan wait", "辺張待ち"],
+        FuReason::Tanki => ["Tanki machi", "Tanki wait", "単騎待ち"],
+        FuReason::RoundUp => ["Kiriage", "Round up", "切り上げ"],
+        FuReason::NotWinning => ["Agari nashi", "Not winning", "和了なし"],
+        FuReason::YakuhaiPairNorth => ["Peihai jantou", "Yakuhai pair (north)", "北の雀頭"],
+    }
+}
+
+fn wait_names(wait: Wait) -> [&'static str; 3] {
+    match wait {
+        Wait::Ryanmen => ["Ryanmen", "Two-sided wait", "両面待ち"],
+        Wait::Kanchan => ["Kanchan", "Closed wait", "嵌張待ち"],
+        Wait::Penchan => ["Penchan", "Edge wait", "辺張待ち"],
+        Wait::Shanpon => ["Shanpon", "Dual pon wait", "双碰待ち"],
+        Wait::Tanki => ["Tanki", "Single wait", "単騎待ち"],
+        Wait::Kokushi13 => ["Kokushi 13-sided", "Thirteen-sided wait", "国士無双十三面待ち"],
+    }
+}
+
+fn limit_names(limit: Limit) -> [&'static str; 3] {
+    match limit {
+        Limit::NoLimit => ["Mangan miman", "No limit", "満貫未満"],
+        Limit::Mangan => ["Mangan", "Mangan", "満貫"],
+        Limit::Haneman => ["Haneman", "Haneman", "跳満"],
+        Limit::Baiman => ["Baiman", "Baiman", "倍満"],
+        Limit::Sanbaiman => ["Sanbaiman", "Sanbaiman", "三倍満"],
+        Limit::Yakuman => ["Yakuman", "Yakuman", "役満"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yaku::{ALL_YAKU, ALL_YAKUMAN};
+    use itertools::Itertools;
+
+    const LOCALES: [Locale; 3] = [Locale::Romaji, Locale::English, Locale::Japanese];
+
+    fn all_han_reasons() -> Vec<HanReason> {
+        ALL_YAKU
+            .iter()
+            .map(|&y| HanReason::Yaku(y))
+            .chain(ALL_YAKUMAN.iter().map(|&y| HanReason::Yakuman(y)))
+            .collect()
+    }
+
+    // Every variant, in declaration order
+    fn all_fu_reasons() -> Vec<FuReason> {
+        let fu_reasons = vec![
+            FuReason::Base,
+            FuReason::Chiitoitsu,
+            FuReason::ClosedRon,
+            FuReason::TsumoNoPinfu,
+            FuReason::OpenPinfu,
+            FuReason::YakuhaiPairColours,
+            FuReason::YakuhaiPairRoundWind,
+            FuReason::YakuhaiPairPlayerWind,
+            FuReason::OpenTripletHonours,
+            FuReason::OpenTripletTerminals,
+            FuReason::OpenTripletSimples,
+            FuReason::ClosedTripletHonours,
+            FuReason::ClosedTripletTerminals,
+            FuReason::ClosedTripletSimples,
+            FuReason::OpenQuadHonours,
+            FuReason::OpenQuadTerminals,
+            FuReason::OpenQuadSimples,
+            FuReason::ClosedQuadHonours,
+            FuReason::ClosedQuadTerminals,
+            FuReason::ClosedQuadSimples,
+            FuReason::Kanchan,
+            FuReason::Penchan,
+            FuReason::Tanki,
+            FuReason::RoundUp,
+            FuReason::NotWinning,
+            FuReason::YakuhaiPairNorth,
+        ];
+        assert_eq!(fu_reasons.len(), FuReason::YakuhaiPairNorth as usize + 1);
+        fu_reasons
+    }
+
+    fn all_waits() -> Vec<Wait> {
+        let waits = vec![
+            Wait::Ryanmen,
+            Wait::Kanchan,
+            Wait::Penchan,
+            Wait::Shanpon,
+            Wait::Tanki,
+            Wait::Kokushi13,
+        ];
+        assert_eq!(waits.len(), Wait::Kokushi13 as usize + 1);
+        waits
+    }
+
+    fn all_limits() -> Vec<Limit> {
+        let limits = vec![
+            Limit::NoLimit,
+            Limit::Mangan,
+            Limit::Haneman,
+            Limit::Baiman,
+            Limit::Sanbaiman,
+            Limit::Yakuman,
+        ];
+        assert_eq!(limits.len(), Limit::Yakuman as usize + 1);
+        limits
+    }
+
+    // Every name is present and unique within its locale, and Japanese names are not romanised
+    fn check_names(names: &dyn Fn(Locale) -> Vec<&'static str>) {
+        for &locale in LOCALES.iter() {
+            let names = names(locale);
+            assert!(names.iter().all(|n| !n.is_empty()), "Missing name in {:?}", locale);
+            assert_eq!(
+                names.iter().unique().count(),
+                names.len(),
+                "Duplicate name in {:?}",
+                locale
+            );
+            if locale == Locale::Japanese {
+                assert!(names.iter().all(|n| !n.is_ascii()), "Untranslated name");
+            }
+        }
+    }
+
+    #[test]
+    fn test_han_reason_names() {
+        check_names(&|locale| all_han_reasons().iter().map(|h| h.name(locale)).collect());
+    }
+
+    #[test]
+    fn test_fu_reason_names() {
+        check_names(&|locale| all_fu_reasons().iter().map(|f| f.name(locale)).collect());
+    }
+
+    #[test]
+    fn test_wait_names() {
+        check_names(&|locale| all_waits().iter().map(|w| w.name(locale)).collect());
+    }
+
+    #[test]
+    fn test_limit_names() {
+        check_names(&|locale| all_limits().iter().map(|l| l.name(locale)).collect());
+    }
+
+    #[test]
+    fn test_display_is_romaji_or_english() {
+        for h in all_han_reasons() {
+            assert_eq!(h.to_string(), h.name(Locale::Romaji));
+        }
+        for f in all_fu_reasons() {
+            assert_eq!(f.to_string(), f.name(Locale::English));
+        }
+        for w in all_waits() {
+            assert_eq!(w.to_string(), w.name(Locale::Romaji));
+        }
+        for l in all_limits() {
+            assert_eq!(l.to_string(), l.name(Locale::English));
+        }
+    }
+}
@@ -1,10 +1,8 @@
 use crate::{
-    tile::{all_tiles, tile_from_string, Tile},
-    types::{Call, CallType},
+    tile::{tile_from_string, Tile},
+    types::{Call, CallType, Meld, MeldKind, MeldOrigin, Wait},
 };
 
-pub(crate) type Meld = Vec<Tile>;
-
 impl Call {
     pub fn new(ctype: CallType, tile: &str) -> Call { Call { ctype, tile: tile_from_string(tile) } }
 
@@ -16,7 +14,18 @@ impl Call {
 
     pub fn ankan(tile: Tile) -> Call { Call { ctype: CallType::Ankan, tile } }
 
+    /// The meld formed by the call. Closed kans are concealed; all other calls are called.
     pub fn meld(&self) -> Meld {
+        let origin = match self.ctype {
+            CallType::Ankan => MeldOrigin::Concealed,
+            CallType::NoCall => panic!("No meld for NoCall"),
+            _ => MeldOrigin::Called,
+        };
+        Meld::new(self.tiles(), origin)
+    }
+
+    /// The tiles in the call.
+    pub fn tiles(&self) -> Vec<Tile> {
         match self.ctype {
             CallType::Chi => vec![self.tile, self.tile.next(), self.tile.next().next()],
             CallType::Pon => vec![self.tile, self.tile, self.tile],
@@ -26,33 +35,46 @@ impl Call {
     }
 }
 
-// TODO: Could optimize by precomputing/hardcoding these (21 and 34)
+impl Meld {
+    /// Makes a meld from its tiles, which must be in order. Panics if the tiles don't form a meld.
+    pub(crate) fn new(tiles: Vec<Tile>, origin: MeldOrigin) -> Meld {
+        let kind = if is_sequence(&tiles) {
+            MeldKind::Sequence
+        } else if is_triplet(&tiles) {
+            MeldKind::Triplet
+        } else if is_quadruplet(&tiles) {
+            MeldKind::Quad
+        } else if is_shanpon(&tiles) {
+            MeldKind::Pair
+        } else {
+            panic!("Not a meld: {:?}", tiles);
+        };
+        Meld { tiles, kind, origin }
+    }
 
-fn all_sequences() -> Vec<Meld> {
-    all_tiles()
-        .into_iter()
-        .filter(|t| t.is_number() && (t.number() != 8) && (t.number() != 9))
-        .map(|t| vec![t, t.next(), t.next().next()])
-        .collect()
-}
+    pub(crate) fn concealed(tiles: Vec<Tile>) -> Meld { Meld::new(tiles, MeldOrigin::Concealed) }
 
-fn all_triplets() -> Vec<Meld> { all_tiles().into_iter().map(|t| vec![t, t, t]).collect() }
+    pub fn tiles(&self) -> &[Tile] { &self.tiles }
 
-pub(crate) fn possible_melds(tiles: &[Tile]) -> Vec<Meld> {
-    let mut possible_melds = possible_sequences(tiles);
-    possible_melds.append(&mut possible_triplets(tiles));
-    possible_melds
-}
+    /// The first tile in the meld.
+    pub fn tile(&self) -> Tile { self.tiles[0] }
 
-pub(crate) fn possible_sequences(tiles: &[Tile]) -> Vec<Meld> {
-    all_sequences().into_iter().filter(|ts| ts.iter().all(|t| tiles.contains(t))).collect()
-}
+    pub fn kind(&self) -> MeldKind { self.kind }
+
+    pub fn origin(&self) -> MeldOrigin { self.origin }
+
+    /// Whether the meld was called, making the hand open.
+    pub fn is_open(&self) -> bool { self.origin == MeldOrigin::Called }
 
-pub(crate) fn possible_triplets(tiles: &[Tile]) -> Vec<Meld> {
-    all_triplets()
-        .into_iter()
-        .filter(|triplet| tiles.iter().filter(|&tile| tile == &triplet[0]).count() >= 3)
-        .collect()
+    /// Whether the meld was formed entirely from drawn tiles (e.g. a closed triplet for sanankou).
+    pub fn is_concealed(&self) -> bool { self.origin == MeldOrigin::Concealed }
+
+    pub fn is_sequence(&self) -> bool { self.kind == MeldKind::Sequence }
+
+    /// Whether the meld is a triplet or a quad.
+    pub fn is_triplet_or_quad(&self) -> bool {
+        self.kind == MeldKind::Triplet || self.kind == MeldKind::Quad
+    }
 }
 
 pub(crate) fn is_triplet(tiles: &[Tile]) -> bool {
@@ -98,9 +120,26 @@ pub(crate) fn is_kanchan(tiles: &[Tile]) -> bool {
 
 pub(crate) fn is_shanpon(tiles: &[Tile]) -> bool { tiles.len() == 2 && tiles[0] == tiles[1] }
 
-pub(crate) fn ryanmen_waits(tiles: &Meld) -> Vec<Tile> { vec![tiles[0].prev(), tiles[1].next()] }
+/// Finds the wait shape of the tiles left over after removing the pair and complete melds.
+pub(crate) fn wait_shape(remaining: &[Tile]) -> Wait {
+    if remaining.len() == 1 {
+        Wait::Tanki
+    } else if is_shanpon(remaining) {
+        Wait::Shanpon
+    } else if is_kanchan(remaining) {
+        Wait::Kanchan
+    } else if is_penchan(remaining) {
+        Wait::Penchan
+    } else if is_ryanmen(remaining) {
+        Wait::Ryanmen
+    } else {
+        panic!("Not a wait")
+    }
+}
 
-pub(crate) fn penchan_waits(tiles: &Meld) -> Vec<Tile> {
+pub(crate) fn ryanmen_waits(tiles: &[Tile]) -> Vec<Tile> { vec![tiles[0].prev(), tiles[1].next()] }
+
+pub(crate) fn penchan_waits(tiles: &[Tile]) -> Vec<Tile> {
     match tiles[0].number() {
         1 => vec![tiles[1].next()],
         8 => vec![tiles[0].prev()],
@@ -108,9 +147,9 @@ pub(crate) fn penchan_waits(tiles: &Meld) -> Vec<Tile> {
     }
 }
 
-pub(crate) fn kanchan_waits(tiles: &Meld) -> Vec<Tile> { vec![tiles[0].next()] }
+pub(crate) fn kanchan_waits(tiles: &[Tile]) -> Vec<Tile> { vec![tiles[0].next()] }
 
-pub(crate) fn shanpon_waits(tiles: &Meld) -> Vec<Tile> { vec![tiles[0]] }
+pub(crate) fn shanpon_waits(tiles: &[Tile]) -> Vec<Tile> { vec![tiles[0]] }
 
 pub(crate) fn no_open_calls(calls: &[Call]) -> bool {
     calls.iter().all(|c| c.ctype == CallType::Ankan)
@@ -122,36 +161,49 @@ mod tests {
     use crate::tile::{tile_from_string, tiles_from_string};
 
     #[test]
-    fn test_all_sequences() {
-        assert_eq!(all_sequences().len(), 21);
+    fn test_meld_kind() {
+        assert_eq!(Meld::concealed(tiles_from_string("345p")).kind(), MeldKind::Sequence);
+        assert_eq!(Meld::concealed(tiles_from_string("777z")).kind(), MeldKind::Triplet);
+        assert_eq!(Meld::concealed(tiles_from_string("1111s")).kind(), MeldKind::Quad);
+        assert_eq!(Meld::concealed(tiles_from_string("22m")).kind(), MeldKind::Pair);
     }
+
+    #[test]
+    #[should_panic]
+    fn test_meld_invalid() { Meld::concealed(tiles_from_string("135m")); }
+
     #[test]
-    fn test_all_triplets() {
-        assert_eq!(all_triplets().len(), 34);
+    fn test_call_meld() {
+        let chi = Call::chi(tile_from_string("7p")).meld();
+        assert_eq!(chi.tiles(), tiles_from_string("789p").as_slice());
+        assert_eq!(chi.kind(), MeldKind::Sequence);
+        assert!(chi.is_open());
+
+        let minkan = Call::minkan(tile_from_string("5z")).meld();
+        assert_eq!(minkan.kind(), MeldKind::Quad);
+        assert!(minkan.is_open());
+
+        let ankan = Call::ankan(tile_from_string("5z")).meld();
+        assert_eq!(ankan.kind(), MeldKind::Quad);
+        assert!(!ankan.is_open());
+        assert!(ankan.is_concealed());
     }
 
     #[test]
-    fn test_possible_melds() {
-        let tiles = tiles_from_string("123466777999m");
-        assert_eq!(
-            possible_sequences(&tiles),
-            [tiles_from_string("123m"), tiles_from_string("234m")]
-        );
-        assert_eq!(
-            possible_triplets(&tiles),
-            [tiles_from_string("777m"), tiles_from_string("999m")]
-        );
+    fn test_wait_shape() {
+        assert_eq!(wait_shape(&tiles_from_string("5z")), Wait::Tanki);
+        assert_eq!(wait_shape(&tiles_from_string("99p")), Wait::Shanpon);
+        assert_eq!(wait_shape(&tiles_from_string("46s")), Wait::Kanchan);
+        assert_eq!(wait_shape(&tiles_from_string("89m")), Wait::Penchan);
+        assert_eq!(wait_shape(&tiles_from_string("23m")), Wait::Ryanmen);
     }
 
     #[test]
     fn test_no_open_calls() {
-        assert!(!no_open_calls(&vec![
+        assert!(!no_open_calls(&[
             Call { ctype: CallType::Minkan, tile: tile_from_string("1m") },
             Call { ctype: CallType::Ankan, tile: tile_from_string("2p") }
         ]));
-        assert!(no_open_calls(&vec![Call {
-            ctype: CallType::Ankan,
-            tile: tile_from_string("3s")
-        }]));
+        assert!(no_open_calls(&[Call { ctype: CallType::Ankan, tile: tile_from_string("3s") }]));
     }
 }
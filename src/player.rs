@@ -1,5 +1,5 @@
 use crate::{
-    tile::Tile,
+    tile::{tile_from_string, Tile},
     types::{Call, CallType},
 };
 use itertools::Itertools;
@@ -10,13 +10,37 @@ pub struct Player {
     player_wind: Tile,
     discards: Vec<Tile>,
     calls: Vec<Call>,
+    nukidora: Vec<Tile>,
     total_score: u32,
 }
 
 impl Player {
     pub fn draw(&mut self, tile: Tile) { self.hand.push(tile); }
 
-    pub fn discard(&mut self, idx: usize) -> Tile { self.hand.remove(idx) }
+    /// Discards a tile from the hand into the discard pond.
+    pub fn discard(&mut self, idx: usize) -> Tile {
+        let tile = self.hand.remove(idx);
+        self.discards.push(tile);
+        tile
+    }
+
+    /// Sets aside a north tile from the hand as nukidora (sanma only).
+    pub fn nuki(&mut self, idx: usize) {
+        assert_eq!(self.hand[idx], tile_from_string("4z"), "Only north tiles can be set aside");
+        self.nukidora.push(self.hand.remove(idx));
+    }
+
+    pub fn player_wind(&self) -> Tile { self.player_wind }
+
+    pub fn discards(&self) -> &[Tile] { &self.discards }
+
+    /// The north tiles set aside as nukidora.
+    pub fn nukidora(&self) -> &[Tile] { &self.nukidora }
+
+    /// The number of nukidora, for `HandContext::n_nukidora`.
+    pub fn n_nukidora(&self) -> u8 { self.nukidora.len() as u8 }
+
+    pub fn total_score(&self) -> u32 { self.total_score }
 
     pub fn call(&mut self, call_type: CallType, called_tile: Tile, hand_tile_indices: Vec<usize>) {
         let mut meld = vec![called_tile];
@@ -33,7 +57,7 @@ impl Player {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tile::{tile_from_string, tiles_from_string};
+    use crate::tile::tiles_from_string;
 
     #[test]
     fn test_call_chi() {
@@ -43,6 +67,30 @@ mod tests {
         assert_eq!(player.calls, vec![Call { ctype: CallType::Chi, tile: tile_from_string("2m") }]);
     }
 
+    #[test]
+    fn test_nuki() {
+        let mut player = Player { hand: tiles_from_string("19m4z"), ..Default::default() };
+        player.nuki(2);
+        assert_eq!(player.hand, tiles_from_string("19m"));
+        assert_eq!(player.nukidora(), tiles_from_string("4z").as_slice());
+        assert_eq!(player.n_nukidora(), 1);
+    }
+
+    #[test]
+    fn test_discard() {
+        let mut player = Player { hand: tiles_from_string("19m4z"), ..Default::default() };
+        assert_eq!(player.discard(1), tile_from_string("9m"));
+        assert_eq!(player.hand, tiles_from_string("1m4z"));
+        assert_eq!(player.discards(), tiles_from_string("9m").as_slice());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_nuki_not_north() {
+        let mut player = Player { hand: tiles_from_string("19m4z"), ..Default::default() };
+        player.nuki(0);
+    }
+
     #[test]
     fn test_call_pon() {
         let mut player = Player { hand: tiles_from_string("12334m"), ..Default::default() };
@@ -0,0 +1,238 @@
+//! Riichi declaration and post-riichi kan checks.
+
+use crate::{
+    division::divide,
+    hand::waits,
+    tile::{tile_counts, with_tiles_removed, Tile},
+    types::{Call, CallType, Rules},
+};
+use itertools::Itertools;
+use std::fmt;
+
+/// Represents the reason riichi cannot be declared.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RiichiError {
+    /// The discard is not one of the hand's tiles.
+    DiscardNotInHand,
+    /// The hand has open calls. Closed quads are allowed.
+    OpenHand,
+    /// The hand would not be tenpai after the discard.
+    NotTenpai,
+    /// The player has less than the 1000 points needed for the deposit.
+    NotEnoughPoints,
+    /// The player would not get another draw from the wall.
+    NotEnoughTiles,
+}
+
+impl fmt::Display for RiichiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            RiichiError::DiscardNotInHand => "the discard is not in the hand",
+            RiichiError::OpenHand => "the hand is open",
+            RiichiError::NotTenpai => "the hand is not tenpai after the discard",
+            RiichiError::NotEnoughPoints => "at least 1000 points are needed",
+            RiichiError::NotEnoughTiles => "not enough tiles are left in the wall",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+/// Checks if riichi can be declared with the given discard.
+///
+/// `tiles` should include the drawn tile, and `discard` should be one of them. `tiles_left` is the
+/// number of tiles left in the live wall: every player should have at least one more draw, so
+/// three-player rules (see `Rules::sanma`) need one less tile.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     riichi::{can_declare_riichi, RiichiError},
+///     tile::{tile_from_string, tiles_from_string},
+///     types::Rules,
+/// };
+///
+/// let tiles = tiles_from_string("123m456p789s2355s1z");
+/// let rules = Rules::default();
+///
+/// assert_eq!(can_declare_riichi(&tiles, &[], tile_from_string("1z"), 25000, 40, &rules), Ok(()));
+/// assert_eq!(
+///     can_declare_riichi(&tiles, &[], tile_from_string("1m"), 25000, 40, &rules),
+///     Err(RiichiError::NotTenpai)
+/// );
+/// assert_eq!(
+///     can_declare_riichi(&tiles, &[], tile_from_string("1z"), 25000, 3, &rules),
+///     Err(RiichiError::NotEnoughTiles)
+/// );
+/// ```
+pub fn can_declare_riichi(
+    tiles: &[Tile], calls: &[Call], discard: Tile, points: i64, tiles_left: usize, rules: &Rules,
+) -> Result<(), RiichiError> {
+    if !tiles.contains(&discard) {
+        return Err(RiichiError::DiscardNotInHand);
+    }
+    if calls.iter().any(|call| call.ctype != CallType::Ankan) {
+        return Err(RiichiError::OpenHand);
+    }
+    if !waits(&with_tiles_removed(tiles, &[discard]), calls).is_empty() {
+        if points < 1000 {
+            return Err(RiichiError::NotEnoughPoints);
+        }
+        let n_players = if rules.sanma.is_some() { 3 } else { 4 };
+        if tiles_left < n_players {
+            return Err(RiichiError::NotEnoughTiles);
+        }
+        return Ok(());
+    }
+    Err(RiichiError::NotTenpai)
+}
+
+/// Finds the discards that leave the hand tenpai, ignoring points and the wall.
+///
+/// `tiles` should include the drawn tile.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{riichi::riichi_discards, tile::tiles_from_string};
+///
+/// assert_eq!(
+///     riichi_discards(&tiles_from_string("123m456p789s234s67z"), &[]),
+///     tiles_from_string("67z")
+/// );
+/// ```
+pub fn riichi_discards(tiles: &[Tile], calls: &[Call]) -> Vec<Tile> {
+    tiles
+        .iter()
+        .sorted()
+        .dedup()
+        .filter(|&&t| !waits(&with_tiles_removed(tiles, &[t]), calls).is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Checks if a closed quad can be declared after riichi with the drawn tile.
+///
+/// `tiles` is the hand before the draw, which must hold the other three copies. The quad must not
+/// change the waits, and the three copies must form a triplet in every interpretation of the hand.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     riichi::can_ankan_after_riichi,
+///     tile::{tile_from_string, tiles_from_string},
+/// };
+///
+/// assert!(can_ankan_after_riichi(
+///     &tiles_from_string("111m456p789s2355s"),
+///     &[],
+///     tile_from_string("1m")
+/// ));
+/// // The 1-man can also be read as a pair, waiting on 2-man and 3-man
+/// assert!(!can_ankan_after_riichi(
+///     &tiles_from_string("1112m456p789s555s"),
+///     &[],
+///     tile_from_string("1m")
+/// ));
+/// ```
+pub fn can_ankan_after_riichi(tiles: &[Tile], calls: &[Call], drawn: Tile) -> bool {
+    if tile_counts(tiles)[drawn.index()] != 3 {
+        return false;
+    }
+    let waits_before = waits(tiles, calls);
+    if waits_before.is_empty() {
+        return false;
+    }
+    let mut calls_after = calls.to_vec();
+    calls_after.push(Call::ankan(drawn));
+    let waits_after = waits(&with_tiles_removed(tiles, &[drawn; 3]), &calls_after);
+    if waits_after != waits_before {
+        return false;
+    }
+
+    // Every tenpai division must keep the three copies together as a triplet
+    let triplet = [drawn; 3];
+    divide(tiles)
+        .into_iter()
+        .filter(|d| d.is_tenpai(calls.len()) && !d.waits().is_empty())
+        .all(|d| d.melds.iter().any(|m| m.tiles() == triplet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::{tile_from_string, tiles_from_string};
+
+    fn ts(tiles: &str) -> Vec<Tile> { tiles_from_string(tiles) }
+
+    fn t(tile: &str) -> Tile { tile_from_string(tile) }
+
+    #[test]
+    fn test_can_declare_riichi() {
+        let tiles = ts("123m456p789s2355s1z");
+        let sanma = Rules { sanma: Some(Default::default()), ..Default::default() };
+        assert_eq!(can_declare_riichi(&tiles, &[], t("1z"), 1000, 4, &Rules::default()), Ok(()));
+        assert_eq!(
+            can_declare_riichi(&tiles, &[], t("1z"), 900, 4, &Rules::default()),
+            Err(RiichiError::NotEnoughPoints)
+        );
+        assert_eq!(can_declare_riichi(&tiles, &[], t("1z"), 1000, 3, &sanma), Ok(()));
+        assert_eq!(
+            can_declare_riichi(&tiles, &[], t("1z"), 1000, 3, &Rules::default()),
+            Err(RiichiError::NotEnoughTiles)
+        );
+    }
+
+    #[test]
+    fn test_can_declare_riichi_calls() {
+        let tiles = ts("456p789s2355s1z");
+        assert_eq!(
+            can_declare_riichi(
+                &tiles,
+                &[Call::ankan(t("1m"))],
+                t("1z"),
+                1000,
+                4,
+                &Rules::default()
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            can_declare_riichi(&tiles, &[Call::pon(t("1m"))], t("1z"), 1000, 4, &Rules::default()),
+            Err(RiichiError::OpenHand)
+        );
+    }
+
+    #[test]
+    fn test_can_declare_riichi_missing_discard() {
+        assert_eq!(
+            can_declare_riichi(
+                &ts("123m456p789s2355s1z"),
+                &[],
+                t("9m"),
+                1000,
+                4,
+                &Rules::default()
+            ),
+            Err(RiichiError::DiscardNotInHand)
+        );
+    }
+
+    #[test]
+    fn test_riichi_discards() {
+        // Chiitoitsu
+        assert_eq!(riichi_discards(&ts("1144m2299p3388s15z"), &[]), ts("15z"));
+        assert!(riichi_discards(&ts("147m258p369s12345z"), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_can_ankan_after_riichi() {
+        // The drawn tile must be the fourth copy
+        assert!(!can_ankan_after_riichi(&ts("111m456p789s2355s"), &[], t("2s")));
+        // Waits on 4-souzu, 6-souzu and 7-souzu, but only on 6-souzu after the quad
+        assert!(!can_ankan_after_riichi(&ts("123m456p789s5556s"), &[], t("5s")));
+        // Closed quads from before riichi are allowed
+        assert!(can_ankan_after_riichi(&ts("456p789s2555s"), &[Call::ankan(t("1z"))], t("5s")));
+    }
+}
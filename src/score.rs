@@ -1,9 +1,14 @@
 //! Hand scoring.
 
 use crate::{
+    breakdown::breakdown,
     calculation::{calculate, CalcResult},
+    hand::waits,
     tile::{tile_from_string, Tile},
-    types::{Call, FuReason, HanReason, HandContext, Limit, Points},
+    types::{
+        BreakdownFormat, BreakdownStyle, Call, CallType, FuReason, HanReason, HandContext, Limit,
+        Meld, Points, SanmaPayment, Wait, Yaku,
+    },
 };
 
 /// Represents the scoring results for a single hand configuration (division).
@@ -14,12 +19,63 @@ pub struct ScoreResult {
 }
 
 impl ScoreResult {
-    pub fn pair(&self) -> Vec<Tile> { self.calc_result.pair.clone() }
+    /// The hand's pair, or `None` for chiitoitsu and kokushi musou.
+    pub fn pair(&self) -> Option<Meld> { self.calc_result.pair.clone() }
 
-    pub fn melds(&self) -> Vec<Vec<Tile>> { self.calc_result.melds.clone() }
+    /// The hand's melds: the closed melds, then the meld completed by the winning tile (if not the
+    /// pair), then the called melds.
+    pub fn melds(&self) -> Vec<Meld> { self.calc_result.melds.clone() }
 
     pub fn remaining(&self) -> Vec<Tile> { self.calc_result.remaining.clone() }
 
+    /// The shape of the wait completed by the winning tile.
+    pub fn wait(&self) -> Wait { self.calc_result.wait }
+
+    /// The meld (or pair, for a tanki wait) completed by the winning tile, or `None` for
+    /// chiitoitsu and kokushi musou.
+    pub fn winning_meld(&self) -> Option<Meld> { self.calc_result.winning_meld.clone() }
+
+    /// Whether the hand is closed, i.e. has no calls other than closed kans.
+    pub fn is_closed(&self) -> bool { self.calc_result.melds.iter().all(|m| !m.is_open()) }
+
+    /// Whether the hand was scored as the dealer's.
+    pub fn is_dealer(&self) -> bool { self.context.player_wind == tile_from_string("1z") }
+
+    /// Whether the hand was scored as a win by tsumo.
+    pub fn is_tsumo(&self) -> bool { self.context.is_tsumo }
+
+    pub(crate) fn context(&self) -> &HandContext { &self.context }
+
+    /// A human-readable explanation of the score: the hand composition, the wait, the yaku and
+    /// fu, and the total.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::{
+    ///     score::score,
+    ///     tile::{tile_from_string, tiles_from_string},
+    ///     types::{BreakdownFormat, BreakdownStyle, HandContext},
+    /// };
+    ///
+    /// let context = HandContext {
+    ///     winning_tile: tile_from_string("4s"),
+    ///     is_riichi: true,
+    ///     player_wind: tile_from_string("2z"),
+    ///     ..Default::default()
+    /// };
+    /// let results = score(&tiles_from_string("123m456p789s55p23s"), &vec![], &context);
+    ///
+    /// assert_eq!(
+    ///     results[0].breakdown(BreakdownStyle::Compact, BreakdownFormat::Text),
+    ///     "123m 456p 789s 234s 55p (4s ron, Ryanmen wait): Riichi 1 han, Pinfu 1 han, Base 20 fu, \
+    ///      Closed ron 10 fu = 2 han 30 fu, 2000"
+    /// );
+    /// ```
+    pub fn breakdown(&self, style: BreakdownStyle, format: BreakdownFormat) -> String {
+        breakdown(self, style, format)
+    }
+
     /// The hand's total fu.
     pub fn fu(&self) -> u8 { self.calc_result.fu_reasons.iter().map(|(_, fu)| fu).sum() }
 
@@ -27,22 +83,29 @@ impl ScoreResult {
     pub fn fu_reasons(&self) -> Vec<(FuReason, u8)> { self.calc_result.fu_reasons.clone() }
 
     /// The hand's total han.
+    ///
+    /// This is capped at 13, except under aotenjou.
     pub fn han(&self) -> u8 {
         let han = self.calc_result.han_reasons.iter().map(|(_, han)| han).sum();
-        if han > 13 {
+        if han > 13 && !self.context.rules.aotenjou {
             13
         } else {
             han
         }
     }
 
+    /// Whether the hand has a yaku or yakuman, not counting dora.
+    pub fn has_yaku(&self) -> bool { has_yaku(&self.calc_result) }
+
     /// The yaku or yakuman contributing to hand's total han.
+    ///
+    /// Yaku are left out if there are yakuman, except under aotenjou.
     pub fn han_reasons(&self) -> Vec<(HanReason, u8)> {
+        if self.context.rules.aotenjou {
+            return self.calc_result.han_reasons.clone();
+        }
         let (han_reasons_yakuman, han_reasons_yaku): (Vec<(HanReason, u8)>, Vec<(HanReason, u8)>) =
-            self.calc_result.han_reasons.iter().partition(|(hr, _)| match hr {
-                HanReason::Yakuman(_) => true,
-                _ => false,
-            });
+            self.calc_result.han_reasons.iter().partition(|(hr, _)| hr.is_yakuman());
         if han_reasons_yakuman.len() > 0 {
             han_reasons_yakuman
         } else {
@@ -51,38 +114,52 @@ impl ScoreResult {
     }
 
     /// The hand's limit (or lack of).
+    ///
+    /// The thresholds are given by the rules, see `Rules::limit_thresholds`. There is no limit
+    /// under aotenjou.
     pub fn limit(&self) -> Limit {
-        match self.han() {
-            0 | 1 | 2 => Limit::NoLimit,
-            3 => {
-                if self.fu() >= 70 {
-                    Limit::Mangan
-                } else {
-                    Limit::NoLimit
-                }
-            }
-            4 => {
-                if self.fu() >= 40 {
-                    Limit::Mangan
-                } else {
-                    Limit::NoLimit
-                }
-            }
-            5 => Limit::Mangan,
-            6 | 7 => Limit::Haneman,
-            8 | 9 | 10 => Limit::Baiman,
-            11 | 12 => Limit::Sanbaiman,
-            _ => Limit::Yakuman,
+        let limits = self.context.rules.limit_thresholds();
+        let han = self.han();
+        if self.context.rules.aotenjou {
+            Limit::NoLimit
+        } else if self.is_yakuman() || han >= limits.kazoe_yakuman {
+            Limit::Yakuman
+        } else if han >= limits.sanbaiman {
+            Limit::Sanbaiman
+        } else if han >= limits.baiman {
+            Limit::Baiman
+        } else if han >= limits.haneman {
+            Limit::Haneman
+        } else if han >= 5
+            || (han == 4 && self.fu() >= limits.mangan_4_han_fu)
+            || (han == 3 && self.fu() >= limits.mangan_3_han_fu)
+        {
+            Limit::Mangan
+        } else {
+            Limit::NoLimit
         }
     }
 
+    // Whether the hand has yakuman, as opposed to counting to 13 han
+    fn is_yakuman(&self) -> bool {
+        self.calc_result.han_reasons.iter().any(|(hr, _)| hr.is_yakuman())
+    }
+
     /// The points based on dealer/nondealer and tsumo/ron.
+    ///
+    /// In sanma, tsumo payments follow the payment rule, and there is one less nondealer payer.
+    ///
+    /// Under aotenjou, points are calculated from the base points and saturate at `u64::MAX`.
     pub fn points(&self) -> Points {
         if self.han() == 0 {
             return Points::NoPoints;
         }
-        let (tsumo_base, ron_dealer, ron_nondealer) = self.points_lookup();
-        if self.is_dealer() {
+        let (tsumo_base, ron_dealer, ron_nondealer) = if self.context.rules.aotenjou {
+            self.points_from_base()
+        } else {
+            self.points_lookup()
+        };
+        let points = if self.is_dealer() {
             if self.is_tsumo() {
                 Points::TsumoAll(tsumo_base)
             } else {
@@ -94,25 +171,46 @@ impl ScoreResult {
             } else {
                 Points::Ron(ron_nondealer)
             }
+        };
+        match self.context.rules.sanma {
+            Some(sanma) => sanma_points(points, self.base_points(), sanma.payment),
+            None => points,
         }
     }
 
     /// The total points received.
-    pub fn points_total(&self) -> u16 {
+    pub fn points_total(&self) -> u64 {
+        let n_nondealer_payers = if self.context.rules.sanma.is_some() { 1 } else { 2 };
         match self.points() {
             Points::NoPoints => 0,
-            Points::TsumoAll(a) => a * 3,
-            Points::Tsumo(a, b) => a * 2 + b,
+            Points::TsumoAll(a) => a.saturating_mul(n_nondealer_payers + 1),
+            Points::Tsumo(a, b) => a.saturating_mul(n_nondealer_payers).saturating_add(b),
             Points::Ron(a) => a,
         }
     }
 
-    fn is_tsumo(&self) -> bool { self.context.is_tsumo }
-
-    fn is_dealer(&self) -> bool { self.context.player_wind == tile_from_string("1z") }
+    // The base points (before multiplying by payer), capped at mangan except under aotenjou
+    fn base_points(&self) -> u64 {
+        match self.limit() {
+            Limit::Mangan => 2000,
+            Limit::Haneman => 3000,
+            Limit::Baiman => 4000,
+            Limit::Sanbaiman => 6000,
+            Limit::Yakuman => 8000,
+            Limit::NoLimit => {
+                let doubles = 2u64.checked_pow(self.han() as u32 + 2).unwrap_or(u64::MAX);
+                let base = (self.fu() as u64).saturating_mul(doubles);
+                if self.context.rules.aotenjou {
+                    base
+                } else {
+                    base.min(2000)
+                }
+            }
+        }
+    }
 
     // (tsumo, dealer ron, non-dealer ron)
-    fn points_lookup(&self) -> (u16, u16, u16) {
+    fn points_lookup(&self) -> (u64, u64, u64) {
         match self.limit() {
             Limit::Mangan => (4000, 12000, 8000),
             Limit::Haneman => (6000, 18000, 12000),
@@ -123,7 +221,7 @@ impl ScoreResult {
         }
     }
 
-    fn points_lookup_nolimit(&self) -> (u16, u16, u16) {
+    fn points_lookup_nolimit(&self) -> (u64, u64, u64) {
         match self.han() {
             1 => match self.fu() {
                 30 => (500, 1500, 1000),
@@ -158,17 +256,28 @@ impl ScoreResult {
                 40 => (2600, 7700, 5200),
                 50 => (3200, 9600, 6400),
                 60 => (3900, 11600, 7700),
-                _ => panic!("Invalid fu"),
+                _ => self.points_from_base(),
             },
             4 => match self.fu() {
                 20 => (2600, 7700, 5200),
                 25 => (3200, 9600, 6400),
                 30 => (3900, 11600, 7700),
-                _ => panic!("Invalid fu"),
+                _ => self.points_from_base(),
             },
             _ => panic!("Invalid han"),
         }
     }
+
+    // For aotenjou, and hands below mangan that are not in the lookup table under custom limit
+    // thresholds
+    fn points_from_base(&self) -> (u64, u64, u64) {
+        let base = self.base_points();
+        (
+            round_up_100(base.saturating_mul(2)),
+            round_up_100(base.saturating_mul(6)),
+            round_up_100(base.saturating_mul(4)),
+        )
+    }
 }
 
 /// Scores all winning hand combinations.
@@ -216,10 +325,88 @@ impl ScoreResult {
 /// );
 /// assert_eq!(results[0].han_reasons(), vec![(HanReason::Yaku(Yaku::Chun), 1)]);
 /// ```
+///
+/// Under sanma rules, hands containing 2-man to 8-man or chi calls are invalid and have no results.
+/// With `Rules::reject_kataagari`, kataagari hands (see `is_kataagari`) have no results.
 pub fn score(tiles: &Vec<Tile>, calls: &Vec<Call>, context: &HandContext) -> Vec<ScoreResult> {
+    if context.rules.sanma.is_some() && !is_valid_sanma(tiles, calls, context) {
+        return vec![];
+    }
+    if context.rules.reject_kataagari && is_kataagari(tiles, calls, context) {
+        return vec![];
+    }
     // TODO: no need to clone context?
     calculate(tiles, calls, context)
         .into_iter()
         .map(|calc_result| ScoreResult { calc_result, context: context.clone() })
         .collect()
 }
+
+/// Checks if only some of a tenpai hand's waits have a yaku (kataagari).
+///
+/// Each wait is scored with the given context as the winning tile. Dora alone are not a yaku.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     score::is_kataagari,
+///     tile::{tile_from_string, tiles_from_string},
+///     types::{Call, HandContext},
+/// };
+///
+/// let calls = vec![Call::chi(tile_from_string("2m"))];
+/// let context = HandContext { player_wind: tile_from_string("2z"), ..Default::default() };
+///
+/// // Tanyao on 6-man, but no yaku on 9-man
+/// assert!(is_kataagari(&tiles_from_string("456p678s55s78m"), &calls, &context));
+/// // Tanyao on both 5-man and 8-man
+/// assert!(!is_kataagari(&tiles_from_string("456p678s55s67m"), &calls, &context));
+/// ```
+pub fn is_kataagari(tiles: &Vec<Tile>, calls: &Vec<Call>, context: &HandContext) -> bool {
+    let has_yaku_on = |wait: Tile| {
+        let context = HandContext { winning_tile: wait, ..context.clone() };
+        if context.rules.sanma.is_some() && !is_valid_sanma(tiles, calls, &context) {
+            return None;
+        }
+        Some(calculate(tiles, calls, &context).iter().any(has_yaku))
+    };
+    let yaku_by_wait: Vec<bool> = waits(tiles, calls).into_iter().filter_map(has_yaku_on).collect();
+    yaku_by_wait.contains(&true) && yaku_by_wait.contains(&false)
+}
+
+fn has_yaku(calc_result: &CalcResult) -> bool {
+    calc_result.han_reasons.iter().any(|(hr, _)| {
+        !matches!(hr, HanReason::Yaku(Yaku::Dora | Yaku::Akadora | Yaku::Uradora | Yaku::Nukidora))
+    })
+}
+
+// Adjusts four-player tsumo payments for the absent north seat in sanma
+pub(crate) fn sanma_points(points: Points, base: u64, payment: SanmaPayment) -> Points {
+    match (points, payment) {
+        (Points::TsumoAll(a), SanmaPayment::NorthBisection) => {
+            Points::TsumoAll(a.saturating_add(round_up_100(a / 2)))
+        }
+        (Points::Tsumo(a, b), SanmaPayment::NorthBisection) => {
+            let half = round_up_100(a / 2);
+            Points::Tsumo(a.saturating_add(half), b.saturating_add(half))
+        }
+        (Points::TsumoAll(_), SanmaPayment::TsumoLossFree) => {
+            Points::TsumoAll(round_up_100(base.saturating_mul(3)))
+        }
+        (Points::Tsumo(..), SanmaPayment::TsumoLossFree) => Points::Tsumo(
+            round_up_100(base.saturating_mul(3) / 2),
+            round_up_100(base.saturating_mul(5) / 2),
+        ),
+        (points, _) => points,
+    }
+}
+
+// Checks that no removed tiles or chi calls are used in sanma
+pub(crate) fn is_valid_sanma(tiles: &[Tile], calls: &[Call], context: &HandContext) -> bool {
+    let is_removed = |t: &Tile| t.is_man() && !t.is_terminal();
+    !tiles.iter().chain(std::iter::once(&context.winning_tile)).any(is_removed)
+        && calls.iter().all(|c| c.ctype != CallType::Chi && !is_removed(&c.tile))
+}
+
+fn round_up_100(points: u64) -> u64 { points.div_ceil(100).saturating_mul(100) }
@@ -0,0 +1,958 @@
+//! Point settlement between seats after a hand ends.
+//!
+//! Seats are indexed by seat wind, from 0 (east, the dealer) to 3 (north).
+
+use crate::{
+    hand::{is_nagashi_mangan, is_tenpai},
+    score::{sanma_points, ScoreResult},
+    tile::Tile,
+    types::{Call, CallType, HanReason, Limit, Points, SanmaPayment, Yakuman},
+};
+use itertools::Itertools;
+use std::convert::TryFrom;
+
+/// Represents a seat, indexed by seat wind from 0 (east) to 3 (north).
+pub type Seat = usize;
+
+/// Represents point changes for each seat, indexed by seat.
+pub type Deltas = [i64; 4];
+
+/// Represents the sticks on the table when a hand ends.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Table {
+    /// The number of honba (repeat counters), each worth 300 points to the winner.
+    pub honba: u8,
+    /// The number of 1000-point riichi deposits, including those declared during the hand.
+    pub riichi_sticks: u8,
+}
+
+/// Represents the outcome of settling a hand.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    /// The point changes for each seat. Riichi deposits already placed on the table are not
+    /// deducted again.
+    pub deltas: Deltas,
+    /// Whether the dealer keeps their seat for the next hand.
+    pub dealer_keeps_seat: bool,
+}
+
+/// Represents a call made by the winner, with the seat that discarded the called tile.
+///
+/// Closed kans have no discarder. An added kan should be recorded with the discarder of the
+/// original pon.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallRecord {
+    pub call: Call,
+    pub from: Option<Seat>,
+}
+
+/// Represents pao (responsibility payment) rule options.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PaoRules {
+    /// Whether the discarder of a daiminkan (open kan) is liable for a rinshan kaihou win on the
+    /// replacement draw.
+    pub daiminkan_rinshan: bool,
+}
+
+/// Represents the rule for several players winning on the same discard.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MultiRonRule {
+    /// Every winner is paid by the discarder.
+    AllWin,
+    /// Atamahane: only the first winner in turn order after the discarder wins.
+    HeadBump,
+    /// Every winner is paid on a double ron, but a triple ron is an abortive draw.
+    TripleRonDraw,
+}
+
+/// Represents the outcome of settling several wins on the same discard.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultiRonSettlement {
+    pub settlement: Settlement,
+    /// The seats that were paid, in turn order after the discarder.
+    pub winners: Vec<Seat>,
+    /// The winner collecting the honba and riichi deposits, or `None` on an abortive draw (in
+    /// which case the deposits stay on the table).
+    pub deposit_winner: Option<Seat>,
+    pub is_abortive_draw: bool,
+}
+
+/// Represents a seat's hand at the end of a hand.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FinalHand {
+    /// The 13 closed tiles, excluding calls.
+    pub tiles: Vec<Tile>,
+    pub calls: Vec<Call>,
+    /// The discard pond, used to check for nagashi mangan.
+    pub discards: Vec<Tile>,
+    /// Whether any of the discards were called by another player.
+    pub discards_called: bool,
+}
+
+/// Represents exhaustive draw rule options.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DrawRules {
+    /// Whether riichi deposits stay on the table for the next hand. Otherwise, they are split
+    /// evenly between the tenpai players, with any remainder staying on the table.
+    pub riichi_sticks_carry_over: bool,
+    /// The limit that nagashi mangan is paid as (by tsumo), or `None` if it is not allowed.
+    /// Nagashi mangan payments replace noten payments.
+    pub nagashi_mangan: Option<Limit>,
+    /// The total noten payment (noten bappu), paid by the noten players and split evenly between
+    /// the tenpai players. This is usually 3000, or 2000 in sanma. If it does not split evenly, it
+    /// is rounded down so that each noten player pays each tenpai player the same amount.
+    pub noten_payment: i64,
+    /// How nagashi mangan is paid in sanma, as for a win by tsumo (see `SanmaRules::payment`).
+    pub sanma_payment: SanmaPayment,
+}
+
+impl Default for DrawRules {
+    fn default() -> DrawRules {
+        DrawRules {
+            riichi_sticks_carry_over: true,
+            nagashi_mangan: Some(Limit::Mangan),
+            noten_payment: 3000,
+            sanma_payment: SanmaPayment::default(),
+        }
+    }
+}
+
+/// Represents the outcome of settling an exhaustive draw.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawSettlement {
+    pub settlement: Settlement,
+    /// Whether each seat is tenpai.
+    pub tenpai: [bool; 4],
+    /// The seats paid for nagashi mangan.
+    pub nagashi_mangan: Vec<Seat>,
+    /// The sticks on the table for the next hand, with one more honba.
+    pub next_table: Table,
+}
+
+/// Finds the seat of the player with the given seat wind.
+pub fn seat(player_wind: Tile) -> Seat {
+    assert!(player_wind.is_wind(), "Not a wind: {:?}", player_wind);
+    (player_wind.number() - 1) as Seat
+}
+
+/// Settles a single win, including honba and riichi deposits.
+///
+/// The winner's seat and the win type are taken from the scoring context. The discarder must be
+/// given for a win by ron, and must be `None` for a win by tsumo.
+///
+/// # Panics
+///
+/// Panics if the hand has no yaku, or if the discarder does not match the win type.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     score::score,
+///     settlement::{settle_win, Table},
+///     tile::{tile_from_string, tiles_from_string},
+///     types::HandContext,
+/// };
+///
+/// let context = HandContext {
+///     winning_tile: tile_from_string("6p"),
+///     is_tsumo: false,
+///     round_wind: tile_from_string("1z"),
+///     player_wind: tile_from_string("2z"),
+///     is_riichi: true,
+///     ..Default::default()
+/// };
+/// let results = score(&tiles_from_string("234789m1234566p"), &vec![], &context);
+///
+/// // Riichi, pinfu (2 han 30 fu, read as a ryanmen wait rather than tanki) dealt in by the west
+/// // seat, with 2 honba and 1 riichi stick
+/// let settlement = settle_win(&results[1], Some(2), &Table { honba: 2, riichi_sticks: 1 });
+///
+/// assert_eq!(settlement.deltas, [0, 2000 + 600 + 1000, -(2000 + 600), 0]);
+/// assert!(!settlement.dealer_keeps_seat);
+/// ```
+pub fn settle_win(result: &ScoreResult, discarder: Option<Seat>, table: &Table) -> Settlement {
+    let winner = seat(result.context().player_wind);
+    let mut deltas = hand_payments(result, discarder);
+    add_honba(&mut deltas, winner, discarder, table.honba, n_seats(result));
+    deltas[winner] += 1000 * table.riichi_sticks as i64;
+    Settlement { deltas, dealer_keeps_seat: winner == 0 }
+}
+
+/// Finds the seat liable for a win under pao, if any.
+///
+/// For daisangen and daisuushii, the player who fed the final dragon or wind pon/kan is liable.
+/// The call history should be in the order the calls were made.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     score::score,
+///     settlement::{find_pao, CallRecord, PaoRules},
+///     tile::{tile_from_string, tiles_from_string},
+///     types::{Call, HandContext},
+/// };
+///
+/// let history: Vec<CallRecord> = [("5z", 1), ("6z", 2), ("7z", 3)]
+///     .iter()
+///     .map(|&(tile, from)| CallRecord {
+///         call: Call::pon(tile_from_string(tile)),
+///         from: Some(from),
+///     })
+///     .collect();
+/// let calls: Vec<Call> = history.iter().map(|record| record.call.clone()).collect();
+/// let context =
+///     HandContext { winning_tile: tile_from_string("4p"), is_tsumo: true, ..Default::default() };
+/// let results = score(&tiles_from_string("123m4p"), &calls, &context);
+///
+/// // The north seat fed the final dragon
+/// assert_eq!(find_pao(&results[0], &history, &PaoRules::default()), Some(3));
+/// ```
+pub fn find_pao(result: &ScoreResult, history: &[CallRecord], rules: &PaoRules) -> Option<Seat> {
+    let yakuman: Vec<Yakuman> = result
+        .han_reasons()
+        .iter()
+        .filter_map(|(reason, _)| match reason {
+            HanReason::Yakuman(yakuman) => Some(*yakuman),
+            _ => None,
+        })
+        .collect();
+    if yakuman.contains(&Yakuman::Daisangen) {
+        if let Some(seat) = final_feeder(history, |t| t.is_colour(), 3) {
+            return Some(seat);
+        }
+    }
+    if yakuman.contains(&Yakuman::Daisuushii) {
+        if let Some(seat) = final_feeder(history, |t| t.is_wind(), 4) {
+            return Some(seat);
+        }
+    }
+    if rules.daiminkan_rinshan && result.context().is_rinshan && result.is_tsumo() {
+        return daiminkan_feeder(history);
+    }
+    None
+}
+
+/// Settles a single win where a player may be liable under pao.
+///
+/// On tsumo, the liable player pays the full hand value (and honba) alone. On ron, the liable
+/// player and the discarder each pay half of the hand value, and the discarder pays the honba.
+/// Since yakuman are not stacked, the whole hand value is subject to pao.
+///
+/// # Panics
+///
+/// Panics under the same conditions as [`settle_win`], or if the winner is liable.
+pub fn settle_win_with_pao(
+    result: &ScoreResult, discarder: Option<Seat>, liable: Option<Seat>, table: &Table,
+) -> Settlement {
+    let winner = seat(result.context().player_wind);
+    let liable = match liable {
+        Some(liable) if discarder != Some(liable) => liable,
+        _ => return settle_win(result, discarder, table),
+    };
+    assert_ne!(liable, winner, "Winner cannot be liable");
+    // Validate the win type
+    hand_payments(result, discarder);
+
+    let total = to_delta(result.points_total());
+    let mut deltas = [0; 4];
+    match discarder {
+        Some(loser) => {
+            pay(&mut deltas, liable, winner, total / 2);
+            pay(&mut deltas, loser, winner, total - total / 2);
+        }
+        None => pay(&mut deltas, liable, winner, total),
+    }
+    add_honba(&mut deltas, winner, Some(discarder.unwrap_or(liable)), table.honba, n_seats(result));
+    deltas[winner] += 1000 * table.riichi_sticks as i64;
+    Settlement { deltas, dealer_keeps_seat: winner == 0 }
+}
+
+/// Settles several wins by ron on the same discard.
+///
+/// The honba and riichi deposits go to the first winner in turn order after the discarder. The
+/// dealer keeps their seat if they are among the winners, or on an abortive draw.
+///
+/// # Panics
+///
+/// Panics if there are no results, if any result is not a ron with yaku, if two results are for
+/// the same seat, or if the discarder is a winner.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     score::score,
+///     settlement::{settle_multi_ron, MultiRonRule, Table},
+///     tile::{tile_from_string, tiles_from_string},
+///     types::HandContext,
+/// };
+///
+/// let context = |player_wind| HandContext {
+///     winning_tile: tile_from_string("4m"),
+///     round_wind: tile_from_string("1z"),
+///     player_wind: tile_from_string(player_wind),
+///     ..Default::default()
+/// };
+/// // Chun for the south seat and tanyao for the north seat, both 1 han 40 fu
+/// let south = score(&tiles_from_string("23m456p678s55p777z"), &vec![], &context("2z"));
+/// let north = score(&tiles_from_string("23m456p678s55p444s"), &vec![], &context("4z"));
+///
+/// let table = Table { honba: 1, riichi_sticks: 1 };
+/// // The dealer deals in
+/// let results = settle_multi_ron(&[&north[0], &south[0]], 0, MultiRonRule::AllWin, &table);
+///
+/// assert_eq!(results.winners, vec![1, 3]);
+/// assert_eq!(results.deposit_winner, Some(1));
+/// assert_eq!(results.settlement.deltas, [-(1300 * 2 + 300), 1300 + 300 + 1000, 0, 1300]);
+/// assert!(!results.settlement.dealer_keeps_seat);
+///
+/// let results = settle_multi_ron(&[&north[0], &south[0]], 0, MultiRonRule::HeadBump, &table);
+/// assert_eq!(results.winners, vec![1]);
+/// assert_eq!(results.settlement.deltas, [-(1300 + 300), 1300 + 300 + 1000, 0, 0]);
+/// ```
+pub fn settle_multi_ron(
+    results: &[&ScoreResult], discarder: Seat, rule: MultiRonRule, table: &Table,
+) -> MultiRonSettlement {
+    assert!(!results.is_empty(), "No winners");
+    let by_seat: Vec<(Seat, &ScoreResult)> = results
+        .iter()
+        .map(|result| (seat(result.context().player_wind), *result))
+        // Turn order after the discarder
+        .sorted_by_key(|(winner, _)| (winner + 4 - discarder) % 4)
+        .collect();
+    assert!(
+        by_seat.iter().map(|(winner, _)| winner).unique().count() == by_seat.len(),
+        "Duplicate winners"
+    );
+
+    if rule == MultiRonRule::TripleRonDraw && by_seat.len() == 3 {
+        return MultiRonSettlement {
+            settlement: Settlement { deltas: [0; 4], dealer_keeps_seat: true },
+            winners: vec![],
+            deposit_winner: None,
+            is_abortive_draw: true,
+        };
+    }
+    let n_winners = if rule == MultiRonRule::HeadBump { 1 } else { by_seat.len() };
+
+    let mut deltas = [0; 4];
+    for (_, result) in by_seat.iter().take(n_winners) {
+        let payments = hand_payments(result, Some(discarder));
+        deltas.iter_mut().zip(payments.iter()).for_each(|(delta, payment)| *delta += payment);
+    }
+    let deposit_winner = by_seat[0].0;
+    add_honba(&mut deltas, deposit_winner, Some(discarder), table.honba, n_seats(by_seat[0].1));
+    deltas[deposit_winner] += 1000 * table.riichi_sticks as i64;
+
+    let winners: Vec<Seat> = by_seat.iter().take(n_winners).map(|(winner, _)| *winner).collect();
+    MultiRonSettlement {
+        settlement: Settlement { deltas, dealer_keeps_seat: winners.contains(&0) },
+        winners,
+        deposit_winner: Some(deposit_winner),
+        is_abortive_draw: false,
+    }
+}
+
+/// Settles an exhaustive draw (ryuukyoku) with noten bappu or nagashi mangan payments.
+///
+/// `hands` are indexed by seat, with three seats in sanma. The noten players pay a total of
+/// `DrawRules::noten_payment`, split evenly between the tenpai players. If any player has nagashi
+/// mangan (and the rules allow it), each of them is instead paid as if winning by tsumo. The
+/// dealer keeps their seat if they are tenpai.
+///
+/// # Example
+///
+/// ```rust
+/// use toitoi::{
+///     settlement::{settle_exhaustive_draw, DrawRules, FinalHand, Table},
+///     tile::tiles_from_string,
+/// };
+///
+/// let hand = |tiles| FinalHand { tiles: tiles_from_string(tiles), ..Default::default() };
+/// let hands = [
+///     hand("1112345678999m"),
+///     hand("1357m2468p1357s9m"),
+///     hand("4555m123789s333z"),
+///     hand("4559m123789s333z"),
+/// ];
+/// let table = Table { honba: 0, riichi_sticks: 1 };
+/// let draw = settle_exhaustive_draw(&hands, &table, &DrawRules::default());
+///
+/// assert_eq!(draw.tenpai, [true, false, true, false]);
+/// assert_eq!(draw.settlement.deltas, [1500, -1500, 1500, -1500]);
+/// assert!(draw.settlement.dealer_keeps_seat);
+/// assert_eq!(draw.next_table, Table { honba: 1, riichi_sticks: 1 });
+/// ```
+pub fn settle_exhaustive_draw(
+    hands: &[FinalHand], table: &Table, rules: &DrawRules,
+) -> DrawSettlement {
+    let n_seats = hands.len();
+    assert!(n_seats == 3 || n_seats == 4, "Expected 3 or 4 hands");
+    let mut tenpai = [false; 4];
+    for (seat, hand) in hands.iter().enumerate() {
+        tenpai[seat] = is_tenpai(&hand.tiles, &hand.calls);
+    }
+    let n_tenpai = tenpai.iter().filter(|&&t| t).count() as i64;
+    let nagashi_mangan: Vec<Seat> = match rules.nagashi_mangan {
+        Some(_) => (0..n_seats)
+            .filter(|&seat| is_nagashi_mangan(&hands[seat].discards, hands[seat].discards_called))
+            .collect(),
+        None => vec![],
+    };
+
+    let mut deltas = [0; 4];
+    if let (Some(limit), false) = (rules.nagashi_mangan, nagashi_mangan.is_empty()) {
+        let base = limit_base_points(limit);
+        for &winner in nagashi_mangan.iter() {
+            let mut points = if winner == 0 {
+                Points::TsumoAll(2 * base)
+            } else {
+                Points::Tsumo(base, 2 * base)
+            };
+            if n_seats == 3 {
+                points = sanma_points(points, base, rules.sanma_payment);
+            }
+            pay_tsumo(&mut deltas, winner, points, n_seats);
+        }
+    } else if n_tenpai > 0 && n_tenpai < n_seats as i64 {
+        // Each noten player pays each tenpai player the same share, so the payments balance
+        let n_noten = n_seats as i64 - n_tenpai;
+        let share = rules.noten_payment / (n_tenpai * n_noten);
+        for payer in (0..n_seats).filter(|&seat| !tenpai[seat]) {
+            for receiver in (0..n_seats).filter(|&seat| tenpai[seat]) {
+                pay(&mut deltas, payer, receiver, share);
+            }
+        }
+    }
+
+    let mut riichi_sticks = table.riichi_sticks;
+    if !rules.riichi_sticks_carry_over && n_tenpai > 0 {
+        let share = table.riichi_sticks as i64 / n_tenpai;
+        for (delta, &is_tenpai) in deltas.iter_mut().zip(tenpai.iter()) {
+            if is_tenpai {
+                *delta += 1000 * share;
+            }
+        }
+        riichi_sticks -= (share * n_tenpai) as u8;
+    }
+
+    DrawSettlement {
+        settlement: Settlement { deltas, dealer_keeps_seat: tenpai[0] },
+        tenpai,
+        nagashi_mangan,
+        next_table: Table { honba: table.honba + 1, riichi_sticks },
+    }
+}
+
+fn limit_base_points(limit: Limit) -> u64 {
+    match limit {
+        Limit::Mangan => 2000,
+        Limit::Haneman => 3000,
+        Limit::Baiman => 4000,
+        Limit::Sanbaiman => 6000,
+        Limit::Yakuman => 8000,
+        Limit::NoLimit => panic!("Not a limit"),
+    }
+}
+
+// Finds who fed the call completing the given number of melds of a tile group
+fn final_feeder(history: &[CallRecord], in_group: fn(&Tile) -> bool, n: usize) -> Option<Seat> {
+    let group_calls: Vec<&CallRecord> = history
+        .iter()
+        .filter(|record| record.call.ctype != CallType::Chi && in_group(&record.call.tile))
+        .unique_by(|record| record.call.tile)
+        .collect();
+    if group_calls.len() == n {
+        group_calls[n - 1].from
+    } else {
+        None
+    }
+}
+
+// Finds who fed the last call if it was a daiminkan, rather than an added kan
+fn daiminkan_feeder(history: &[CallRecord]) -> Option<Seat> {
+    let (last, earlier) = history.split_last()?;
+    let is_added_kan = earlier
+        .iter()
+        .any(|record| record.call.ctype == CallType::Pon && record.call.tile == last.call.tile);
+    if last.call.ctype == CallType::Minkan && !is_added_kan {
+        last.from
+    } else {
+        None
+    }
+}
+
+// Payments for the hand value alone
+pub(crate) fn hand_payments(result: &ScoreResult, discarder: Option<Seat>) -> Deltas {
+    let winner = seat(result.context().player_wind);
+    assert_eq!(discarder.is_none(), result.is_tsumo(), "Discarder does not match win type");
+    assert_ne!(discarder, Some(winner), "Winner cannot be the discarder");
+    assert!(discarder.unwrap_or(0) < n_seats(result), "No such discarder");
+
+    let mut deltas = [0; 4];
+    match (result.points(), discarder) {
+        (Points::Ron(points), Some(loser)) => pay(&mut deltas, loser, winner, to_delta(points)),
+        (points @ Points::TsumoAll(_), None) | (points @ Points::Tsumo(..), None) => {
+            pay_tsumo(&mut deltas, winner, points, n_seats(result))
+        }
+        (Points::NoPoints, _) => panic!("Hand has no yaku"),
+        _ => panic!("Points do not match win type"),
+    }
+    deltas
+}
+
+// Tsumo payments from every other seat, with the dealer paying more for a nondealer win
+fn pay_tsumo(deltas: &mut Deltas, winner: Seat, points: Points, n_seats: usize) {
+    for payer in others(winner, n_seats) {
+        let points = match points {
+            Points::TsumoAll(points) => points,
+            Points::Tsumo(_, dealer) if payer == 0 => dealer,
+            Points::Tsumo(nondealer, _) => nondealer,
+            _ => panic!("Not a tsumo payment"),
+        };
+        pay(deltas, payer, winner, to_delta(points));
+    }
+}
+
+// Honba go to a single winner: 300 each from the discarder, or 100 each from every payer
+pub(crate) fn add_honba(
+    deltas: &mut Deltas, winner: Seat, discarder: Option<Seat>, honba: u8, n_seats: usize,
+) {
+    match discarder {
+        Some(loser) => pay(deltas, loser, winner, 300 * honba as i64),
+        None => {
+            for payer in others(winner, n_seats) {
+                pay(deltas, payer, winner, 100 * honba as i64);
+            }
+        }
+    }
+}
+
+// Aotenjou points may be too large to settle
+fn to_delta(points: u64) -> i64 { i64::try_from(points).expect("Points too large to settle") }
+
+pub(crate) fn pay(deltas: &mut Deltas, from: Seat, to: Seat, points: i64) {
+    deltas[from] -= points;
+    deltas[to] += points;
+}
+
+pub(crate) fn others(seat: Seat, n_seats: usize) -> impl Iterator<Item = Seat> {
+    (0..n_seats).filter(move |&s| s != seat)
+}
+
+// The north seat is absent in sanma
+fn n_seats(result: &ScoreResult) -> usize {
+    if result.context().rules.sanma.is_some() {
+        3
+    } else {
+        4
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        score::score,
+        tile::{tile_from_string, tiles_from_string},
+        types::{HandContext, Rules, SanmaRules},
+    };
+
+    #[test]
+    fn test_seat() {
+        assert_eq!(seat(tile_from_string("1z")), 0);
+        assert_eq!(seat(tile_from_string("4z")), 3);
+    }
+
+    #[test]
+    fn test_settle_ron() {
+        // Chun, 40 fu, dealt in by the north seat
+        let result = &chun_hand("2z", false)[0];
+        let settlement = settle_win(result, Some(3), &Table::default());
+        assert_eq!(settlement.deltas, [0, 1300, 0, -1300]);
+        assert!(!settlement.dealer_keeps_seat);
+    }
+
+    #[test]
+    fn test_settle_dealer_ron_honba_sticks() {
+        let result = &chun_hand("1z", false)[0];
+        let settlement = settle_win(result, Some(1), &Table { honba: 3, riichi_sticks: 2 });
+        assert_eq!(settlement.deltas, [2000 + 900 + 2000, -(2000 + 900), 0, 0]);
+        assert!(settlement.dealer_keeps_seat);
+    }
+
+    #[test]
+    fn test_settle_nondealer_tsumo_honba() {
+        // Menzen tsumo and chun (2 han 30 fu), 1 honba
+        let result = &chun_hand("3z", true)[0];
+        let settlement = settle_win(result, None, &Table { honba: 1, riichi_sticks: 1 });
+        assert_eq!(settlement.deltas, [-1100, -600, 1100 + 600 * 2 + 1000, -600]);
+    }
+
+    #[test]
+    fn test_settle_dealer_tsumo() {
+        let result = &chun_hand("1z", true)[0];
+        let settlement = settle_win(result, None, &Table { honba: 2, riichi_sticks: 0 });
+        assert_eq!(settlement.deltas, [3 * 1200, -1200, -1200, -1200]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_settle_tsumo_with_discarder() {
+        settle_win(&chun_hand("2z", true)[0], Some(0), &Table::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_settle_ron_by_winner() {
+        settle_win(&chun_hand("2z", false)[0], Some(1), &Table::default());
+    }
+
+    #[test]
+    fn test_find_pao_daisangen() {
+        let history = records(&[("5z", Some(1)), ("6z", Some(2)), ("7z", Some(3))]);
+        assert_eq!(
+            find_pao(&daisangen_hand(&history, true), &history, &PaoRules::default()),
+            Some(3)
+        );
+
+        // A closed kan counts towards the exposed dragons
+        let history = records(&[("5z", Some(1)), ("7z", None), ("6z", Some(2))]);
+        assert_eq!(
+            find_pao(&daisangen_hand(&history, true), &history, &PaoRules::default()),
+            Some(2)
+        );
+
+        // The final dragon quad is closed
+        let history = records(&[("5z", Some(1)), ("6z", Some(2)), ("7z", None)]);
+        assert_eq!(find_pao(&daisangen_hand(&history, true), &history, &PaoRules::default()), None);
+
+        // Added kan on an earlier pon
+        let history =
+            records(&[("5z", Some(1)), ("6z", Some(2)), ("7z", Some(3)), ("5z", Some(1))]);
+        assert_eq!(
+            find_pao(&daisangen_hand(&history, true), &history, &PaoRules::default()),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_find_pao_daisuushii() {
+        let history =
+            records(&[("1z", Some(1)), ("2z", Some(3)), ("3z", Some(3)), ("4z", Some(2))]);
+        let calls: Vec<Call> = history.iter().map(|record| record.call.clone()).collect();
+        let context = HandContext {
+            winning_tile: tile_from_string("5p"),
+            is_tsumo: false,
+            player_wind: tile_from_string("1z"),
+            ..Default::default()
+        };
+        let results = score(&tiles_from_string("5p"), &calls, &context);
+        assert_eq!(find_pao(&results[0], &history, &PaoRules::default()), Some(2));
+    }
+
+    #[test]
+    fn test_find_pao_daiminkan_rinshan() {
+        let history =
+            vec![CallRecord { call: Call::minkan(tile_from_string("9s")), from: Some(0) }];
+        let context = HandContext {
+            winning_tile: tile_from_string("4m"),
+            is_tsumo: true,
+            is_rinshan: true,
+            round_wind: tile_from_string("1z"),
+            player_wind: tile_from_string("2z"),
+            ..Default::default()
+        };
+        let results =
+            score(&tiles_from_string("23m456p678s55p"), &vec![history[0].call.clone()], &context);
+        let rules = PaoRules { daiminkan_rinshan: true };
+        assert_eq!(find_pao(&results[0], &history, &rules), Some(0));
+        assert_eq!(find_pao(&results[0], &history, &PaoRules::default()), None);
+
+        // Added kan on an earlier pon
+        let history = vec![
+            CallRecord { call: Call::pon(tile_from_string("9s")), from: Some(0) },
+            CallRecord { call: Call::minkan(tile_from_string("9s")), from: Some(0) },
+        ];
+        assert_eq!(find_pao(&results[0], &history, &rules), None);
+    }
+
+    #[test]
+    fn test_settle_pao_tsumo() {
+        let history = records(&[("5z", Some(1)), ("6z", Some(2)), ("7z", Some(3))]);
+        let result = daisangen_hand(&history, true);
+        let settlement =
+            settle_win_with_pao(&result, None, Some(3), &Table { honba: 1, riichi_sticks: 1 });
+        assert_eq!(settlement.deltas, [0, 32000 + 300 + 1000, 0, -(32000 + 300)]);
+    }
+
+    #[test]
+    fn test_settle_pao_ron() {
+        let history = records(&[("5z", Some(1)), ("6z", Some(2)), ("7z", Some(3))]);
+        let result = daisangen_hand(&history, false);
+        let settlement =
+            settle_win_with_pao(&result, Some(2), Some(3), &Table { honba: 1, riichi_sticks: 0 });
+        assert_eq!(settlement.deltas, [0, 32000 + 300, -(16000 + 300), -16000]);
+
+        // The liable player dealt in
+        let settlement = settle_win_with_pao(&result, Some(3), Some(3), &Table::default());
+        assert_eq!(settlement.deltas, [0, 32000, 0, -32000]);
+    }
+
+    #[test]
+    fn test_multi_ron_deposit_order() {
+        // West deals in, so north is first in turn order, then east
+        let east = chun_hand_ron("1z");
+        let north = chun_hand_ron("4z");
+        let table = Table { honba: 2, riichi_sticks: 3 };
+        let results = settle_multi_ron(&[&east, &north], 2, MultiRonRule::AllWin, &table);
+        assert_eq!(results.winners, vec![3, 0]);
+        assert_eq!(results.deposit_winner, Some(3));
+        assert_eq!(results.settlement.deltas, [2000, 0, -(2000 + 1300 + 600), 1300 + 600 + 3000]);
+        assert!(results.settlement.dealer_keeps_seat);
+
+        let results = settle_multi_ron(&[&east, &north], 2, MultiRonRule::HeadBump, &table);
+        assert_eq!(results.winners, vec![3]);
+        assert_eq!(results.settlement.deltas, [0, 0, -(1300 + 600), 1300 + 600 + 3000]);
+        assert!(!results.settlement.dealer_keeps_seat);
+    }
+
+    #[test]
+    fn test_multi_ron_triple() {
+        let (east, south, west) = (chun_hand_ron("1z"), chun_hand_ron("2z"), chun_hand_ron("3z"));
+        let table = Table { honba: 1, riichi_sticks: 1 };
+
+        let results =
+            settle_multi_ron(&[&east, &south, &west], 3, MultiRonRule::TripleRonDraw, &table);
+        assert!(results.is_abortive_draw);
+        assert_eq!(results.deposit_winner, None);
+        assert_eq!(results.settlement.deltas, [0; 4]);
+        assert!(results.settlement.dealer_keeps_seat);
+
+        let results = settle_multi_ron(&[&east, &south, &west], 3, MultiRonRule::AllWin, &table);
+        assert_eq!(results.winners, vec![0, 1, 2]);
+        assert_eq!(
+            results.settlement.deltas,
+            [2000 + 300 + 1000, 1300, 1300, -(2000 + 1300 * 2 + 300)]
+        );
+
+        // Double ron is not a draw
+        let results = settle_multi_ron(&[&east, &west], 3, MultiRonRule::TripleRonDraw, &table);
+        assert_eq!(results.winners, vec![0, 2]);
+        assert!(!results.is_abortive_draw);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multi_ron_discarder_wins() {
+        let (east, south) = (chun_hand_ron("1z"), chun_hand_ron("2z"));
+        settle_multi_ron(&[&east, &south], 1, MultiRonRule::AllWin, &Table::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multi_ron_duplicate_winner() {
+        let (east, east_again) = (chun_hand_ron("1z"), chun_hand_ron("1z"));
+        settle_multi_ron(&[&east, &east_again], 1, MultiRonRule::AllWin, &Table::default());
+    }
+
+    #[test]
+    fn test_exhaustive_draw_payments() {
+        let (tenpai, noten) = ("4555m123789s333z", "4559m123789s333z");
+        let table = Table::default();
+        let rules = DrawRules::default();
+
+        let draw =
+            settle_exhaustive_draw(&final_hands([noten, tenpai, noten, noten]), &table, &rules);
+        assert_eq!(draw.settlement.deltas, [-1000, 3000, -1000, -1000]);
+        assert!(!draw.settlement.dealer_keeps_seat);
+
+        let draw =
+            settle_exhaustive_draw(&final_hands([tenpai, tenpai, noten, tenpai]), &table, &rules);
+        assert_eq!(draw.settlement.deltas, [1000, 1000, -3000, 1000]);
+        assert!(draw.settlement.dealer_keeps_seat);
+
+        for hands in [[tenpai; 4], [noten; 4]].iter() {
+            let draw = settle_exhaustive_draw(&final_hands(*hands), &table, &rules);
+            assert_eq!(draw.settlement.deltas, [0; 4]);
+        }
+    }
+
+    #[test]
+    fn test_exhaustive_draw_sanma() {
+        let (tenpai, noten) = ("4555m123789s333z", "4559m123789s333z");
+        let hands = final_hands([noten, tenpai, noten, noten]);
+        let table = Table::default();
+        let rules = DrawRules { noten_payment: 2000, ..Default::default() };
+
+        let draw = settle_exhaustive_draw(&hands[..3], &table, &rules);
+        assert_eq!(draw.settlement.deltas, [-1000, 2000, -1000, 0]);
+        assert_eq!(draw.tenpai, [false, true, false, false]);
+
+        let mut hands = final_hands([tenpai, noten, tenpai, noten]);
+        let draw = settle_exhaustive_draw(&hands[..3], &table, &rules);
+        assert_eq!(draw.settlement.deltas, [1000, -2000, 1000, 0]);
+
+        // Nagashi mangan is paid by two players, with no fourth seat
+        hands[1].discards = tiles_from_string("19p1s1122z");
+        let draw = settle_exhaustive_draw(&hands[..3], &table, &rules);
+        assert_eq!(draw.settlement.deltas, [-4000, 6000, -2000, 0]);
+    }
+
+    #[test]
+    fn test_exhaustive_draw_uneven_noten_payment() {
+        let (tenpai, noten) = ("4555m123789s333z", "4559m123789s333z");
+        let hands = final_hands([tenpai, tenpai, noten, tenpai]);
+        let rules = DrawRules { noten_payment: 1000, ..Default::default() };
+
+        let draw = settle_exhaustive_draw(&hands, &Table::default(), &rules);
+        assert_eq!(draw.settlement.deltas, [333, 333, -999, 333]);
+        assert_eq!(draw.settlement.deltas.iter().sum::<i64>(), 0);
+    }
+
+    #[test]
+    fn test_exhaustive_draw_sanma_nagashi_mangan() {
+        let mut hands = final_hands(["4559m123789s333z"; 4]);
+        hands[1].discards = tiles_from_string("19p1s1122z");
+        let table = Table::default();
+        let deltas = |hands: &[FinalHand], sanma_payment| {
+            let rules = DrawRules { sanma_payment, ..Default::default() };
+            settle_exhaustive_draw(&hands[..3], &table, &rules).settlement.deltas
+        };
+
+        assert_eq!(deltas(&hands, SanmaPayment::TsumoLoss), [-4000, 6000, -2000, 0]);
+        assert_eq!(deltas(&hands, SanmaPayment::NorthBisection), [-5000, 8000, -3000, 0]);
+        assert_eq!(deltas(&hands, SanmaPayment::TsumoLossFree), [-5000, 8000, -3000, 0]);
+
+        // Dealer nagashi mangan
+        hands[0].discards = tiles_from_string("9s77z");
+        hands[1].discards = vec![];
+        assert_eq!(deltas(&hands, SanmaPayment::TsumoLoss), [8000, -4000, -4000, 0]);
+        assert_eq!(deltas(&hands, SanmaPayment::NorthBisection), [12000, -6000, -6000, 0]);
+    }
+
+    #[test]
+    fn test_exhaustive_draw_with_calls() {
+        let mut hands = final_hands(["4559m123789s333z"; 4]);
+        // Waiting on 1-man only, since the other three 4-man have been called
+        hands[2] = FinalHand {
+            tiles: tiles_from_string("1234m567p"),
+            calls: vec![Call::pon(tile_from_string("4m"))],
+            ..Default::default()
+        };
+        // Waiting on a tile held four times
+        hands[3] =
+            FinalHand { tiles: tiles_from_string("1111m234p567s777z"), ..Default::default() };
+        let draw = settle_exhaustive_draw(&hands, &Table::default(), &DrawRules::default());
+        assert_eq!(draw.tenpai, [false, false, true, false]);
+    }
+
+    #[test]
+    fn test_exhaustive_draw_riichi_sticks() {
+        let (tenpai, noten) = ("4555m123789s333z", "4559m123789s333z");
+        let hands = final_hands([tenpai, noten, tenpai, noten]);
+        let table = Table { honba: 2, riichi_sticks: 3 };
+
+        let draw = settle_exhaustive_draw(&hands, &table, &DrawRules::default());
+        assert_eq!(draw.next_table, Table { honba: 3, riichi_sticks: 3 });
+
+        let rules = DrawRules { riichi_sticks_carry_over: false, ..Default::default() };
+        let draw = settle_exhaustive_draw(&hands, &table, &rules);
+        assert_eq!(draw.settlement.deltas, [1500 + 1000, -1500, 1500 + 1000, -1500]);
+        assert_eq!(draw.next_table, Table { honba: 3, riichi_sticks: 1 });
+    }
+
+    #[test]
+    fn test_exhaustive_draw_nagashi_mangan() {
+        let (tenpai, noten) = ("4555m123789s333z", "4559m123789s333z");
+        let mut hands = final_hands([tenpai, noten, tenpai, noten]);
+        hands[1].discards = tiles_from_string("19m19p1s1122z");
+        let table = Table { honba: 1, riichi_sticks: 1 };
+
+        let draw = settle_exhaustive_draw(&hands, &table, &DrawRules::default());
+        assert_eq!(draw.nagashi_mangan, vec![1]);
+        assert_eq!(draw.settlement.deltas, [-4000, 8000, -2000, -2000]);
+        assert!(draw.settlement.dealer_keeps_seat);
+        assert_eq!(draw.next_table, Table { honba: 2, riichi_sticks: 1 });
+
+        // Paid as yakuman
+        let rules = DrawRules { nagashi_mangan: Some(Limit::Yakuman), ..Default::default() };
+        let draw = settle_exhaustive_draw(&hands, &table, &rules);
+        assert_eq!(draw.settlement.deltas, [-16000, 32000, -8000, -8000]);
+
+        // Not allowed, so noten payments apply
+        let rules = DrawRules { nagashi_mangan: None, ..Default::default() };
+        let draw = settle_exhaustive_draw(&hands, &table, &rules);
+        assert!(draw.nagashi_mangan.is_empty());
+        assert_eq!(draw.settlement.deltas, [1500, -1500, 1500, -1500]);
+
+        // Dealer and nondealer nagashi mangan, with a discard called away from the nondealer
+        hands[0].discards = tiles_from_string("9s77z");
+        hands[2].discards = tiles_from_string("1m");
+        hands[2].discards_called = true;
+        let draw = settle_exhaustive_draw(&hands, &table, &DrawRules::default());
+        assert_eq!(draw.nagashi_mangan, vec![0, 1]);
+        assert_eq!(draw.settlement.deltas, [12000 - 4000, 8000 - 4000, -4000 - 2000, -4000 - 2000]);
+    }
+
+    #[test]
+    fn test_settle_sanma_tsumo() {
+        let context = HandContext {
+            winning_tile: tile_from_string("4s"),
+            is_tsumo: true,
+            round_wind: tile_from_string("1z"),
+            player_wind: tile_from_string("3z"),
+            rules: Rules { sanma: Some(SanmaRules::default()), ..Default::default() },
+            ..Default::default()
+        };
+        let result = &score(&tiles_from_string("11m456p678s23s555z"), &vec![], &context)[0];
+        let settlement = settle_win(result, None, &Table { honba: 1, riichi_sticks: 0 });
+        assert_eq!(settlement.deltas, [-1100, -600, 1700, 0]);
+    }
+
+    fn final_hands(tiles: [&str; 4]) -> [FinalHand; 4] {
+        let hand = |tiles| FinalHand { tiles: tiles_from_string(tiles), ..Default::default() };
+        [hand(tiles[0]), hand(tiles[1]), hand(tiles[2]), hand(tiles[3])]
+    }
+
+    fn chun_hand_ron(player_wind: &str) -> ScoreResult { chun_hand(player_wind, false).remove(0) }
+
+    fn records(calls: &[(&str, Option<Seat>)]) -> Vec<CallRecord> {
+        calls
+            .iter()
+            .map(|&(tile, from)| {
+                let tile = tile_from_string(tile);
+                let call = if from.is_some() { Call::pon(tile) } else { Call::ankan(tile) };
+                CallRecord { call, from }
+            })
+            .collect()
+    }
+
+    // Nondealer's daisangen, waiting on 4-pin
+    fn daisangen_hand(history: &[CallRecord], is_tsumo: bool) -> ScoreResult {
+        let calls: Vec<Call> =
+            history.iter().map(|record| record.call.clone()).unique_by(|c| c.tile).collect();
+        let context = HandContext {
+            winning_tile: tile_from_string("4p"),
+            is_tsumo,
+            round_wind: tile_from_string("1z"),
+            player_wind: tile_from_string("2z"),
+            ..Default::default()
+        };
+        score(&tiles_from_string("123m4p"), &calls, &context).remove(0)
+    }
+
+    // Closed hand with a chun triplet, won on a ryanmen wait
+    fn chun_hand(player_wind: &str, is_tsumo: bool) -> Vec<ScoreResult> {
+        let context = HandContext {
+            winning_tile: tile_from_string("4m"),
+            is_tsumo,
+            round_wind: tile_from_string("1z"),
+            player_wind: tile_from_string(player_wind),
+            ..Default::default()
+        };
+        score(&tiles_from_string("23m456p678s55p777z"), &vec![], &context)
+    }
+}
@@ -0,0 +1,261 @@
+//! Precomputed tables of the ways to split a single suit into melds and at most one pair.
+//!
+//! Each suit's tile counts are encoded as a base-5 key. The tables are generated on first use by
+//! enumerating every combination of up to four melds plus an optional pair, so splitting a suit
+//! at runtime is a single lookup rather than a search.
+
+use crate::tile::{all_tiles, Tile};
+use std::{collections::HashMap, sync::OnceLock};
+
+/// A meld within a suit, by kind and the (zero-based) position of its first tile in the suit.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum SuitMeld {
+    Sequence(u8),
+    Triplet(u8),
+}
+
+/// One way of splitting a suit into melds and at most one pair.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct SuitSplit {
+    pub(crate) pair: Option<u8>,
+    pub(crate) melds: Vec<SuitMeld>,
+}
+
+type Table = HashMap<u32, Vec<SuitSplit>>;
+
+const MAX_MELDS: usize = 4;
+
+/// Returns all ways to split the given suit (0-3 for man, pin, sou and honours) of `counts`.
+///
+/// Suits with more than four copies of a tile have no splits.
+pub(crate) fn suit_splits(counts: &[u8; 34], suit: usize) -> &'static [SuitSplit] {
+    let (table, suit_counts) = match suit {
+        0..=2 => (number_table(), &counts[(suit * 9)..(suit * 9 + 9)]),
+        3 => (honour_table(), &counts[27..34]),
+        _ => panic!("Invalid suit: {}", suit),
+    };
+    if suit_counts.iter().any(|&c| c > 4) {
+        return &[];
+    }
+    table.get(&key(suit_counts)).map_or(&[], |splits| splits.as_slice())
+}
+
+/// Checks if `counts` can be split into melds and exactly one pair.
+pub(crate) fn is_complete(counts: &[u8; 34]) -> bool {
+    let mut n_pairs = 0;
+    for suit in 0..4 {
+        let n_tiles: u8 = suit_range(suit).map(|i| counts[i]).sum();
+        let needs_pair = match n_tiles % 3 {
+            0 => false,
+            2 => true,
+            _ => return false,
+        };
+        if !suit_splits(counts, suit).iter().any(|s| s.pair.is_some() == needs_pair) {
+            return false;
+        }
+        if needs_pair {
+            n_pairs += 1;
+        }
+    }
+    n_pairs == 1
+}
+
+/// Finds all ways to split `counts` entirely into melds, without a pair.
+pub(crate) fn meld_splits(counts: &[u8; 34]) -> Vec<Vec<Vec<Tile>>> {
+    let mut results: Vec<Vec<Vec<Tile>>> = vec![vec![]];
+    for suit in 0..4 {
+        let splits: Vec<&SuitSplit> =
+            suit_splits(counts, suit).iter().filter(|s| s.pair.is_none()).collect();
+        if splits.is_empty() {
+            return vec![];
+        }
+        results = results
+            .into_iter()
+            .flat_map(|melds| {
+                splits.iter().map(move |split| {
+                    let mut melds = melds.clone();
+                    melds.extend(split.melds.iter().map(|m| to_meld(suit, m)));
+                    melds
+                })
+            })
+            .collect();
+    }
+    results
+}
+
+/// Finds all tiles that would complete `counts` into melds and exactly one pair.
+pub(crate) fn completing_tiles(counts: &[u8; 34]) -> Vec<Tile> {
+    let mut counts = *counts;
+    let mut tiles = vec![];
+    for tile in all_tiles() {
+        // Reject impossible waits (not enough tiles)
+        if counts[tile.index()] >= 4 {
+            continue;
+        }
+        counts[tile.index()] += 1;
+        if is_complete(&counts) {
+            tiles.push(tile);
+        }
+        counts[tile.index()] -= 1;
+    }
+    tiles
+}
+
+fn number_table() -> &'static Table {
+    static TABLE: OnceLock<Table> = OnceLock::new();
+    TABLE.get_or_init(|| build_table(9, true))
+}
+
+fn honour_table() -> &'static Table {
+    static TABLE: OnceLock<Table> = OnceLock::new();
+    TABLE.get_or_init(|| build_table(7, false))
+}
+
+fn build_table(size: usize, with_sequences: bool) -> Table {
+    let mut melds = vec![];
+    if with_sequences {
+        melds.extend((0..(size as u8 - 2)).map(SuitMeld::Sequence));
+    }
+    melds.extend((0..size as u8).map(SuitMeld::Triplet));
+
+    let mut table = Table::new();
+    add_splits(&melds, 0, &mut vec![0; size], &mut vec![], &mut table);
+    table
+}
+
+// Records the current combination of melds (with and without each possible pair), then extends it
+// with every meld from `start` onwards, so that each multiset of melds is visited exactly once.
+fn add_splits(
+    melds: &[SuitMeld], start: usize, counts: &mut [u8], chosen: &mut Vec<SuitMeld>,
+    table: &mut Table,
+) {
+    table.entry(key(counts)).or_default().push(SuitSplit { pair: None, melds: chosen.clone() });
+    for i in 0..counts.len() {
+        if counts[i] <= 2 {
+            counts[i] += 2;
+            table
+                .entry(key(counts))
+                .or_default()
+                .push(SuitSplit { pair: Some(i as u8), melds: chosen.clone() });
+            counts[i] -= 2;
+        }
+    }
+
+    if chosen.len() == MAX_MELDS {
+        return;
+    }
+    for (j, meld) in melds.iter().enumerate().skip(start) {
+        let positions = positions(meld);
+        for &p in positions.iter() {
+            counts[p] += 1;
+        }
+        if positions.iter().all(|&p| counts[p] <= 4) {
+            chosen.push(*meld);
+            add_splits(melds, j, counts, chosen, table);
+            chosen.pop();
+        }
+        for &p in positions.iter() {
+            counts[p] -= 1;
+        }
+    }
+}
+
+fn positions(meld: &SuitMeld) -> [usize; 3] {
+    match *meld {
+        SuitMeld::Sequence(p) => [p as usize, p as usize + 1, p as usize + 2],
+        SuitMeld::Triplet(p) => [p as usize; 3],
+    }
+}
+
+// Counts above 4 would alias another key
+fn key(suit_counts: &[u8]) -> u32 {
+    suit_counts.iter().fold(0, |key, &c| {
+        debug_assert!(c <= 4, "More than four copies of a tile");
+        key * 5 + c as u32
+    })
+}
+
+fn suit_range(suit: usize) -> std::ops::Range<usize> {
+    if suit == 3 {
+        27..34
+    } else {
+        (suit * 9)..(suit * 9 + 9)
+    }
+}
+
+fn to_meld(suit: usize, meld: &SuitMeld) -> Vec<Tile> {
+    positions(meld).iter().map(|p| Tile::from_index(suit * 9 + p)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::{tile_counts, tiles_from_string};
+
+    #[test]
+    fn test_suit_splits() {
+        let counts = tile_counts(&tiles_from_string("111222333m"));
+        assert_eq!(
+            suit_splits(&counts, 0),
+            [
+                SuitSplit {
+                    pair: None,
+                    melds: vec![
+                        SuitMeld::Sequence(0),
+                        SuitMeld::Sequence(0),
+                        SuitMeld::Sequence(0)
+                    ]
+                },
+                SuitSplit {
+                    pair: None,
+                    melds: vec![SuitMeld::Triplet(0), SuitMeld::Triplet(1), SuitMeld::Triplet(2)]
+                },
+            ]
+        );
+        // Empty suits have exactly one (empty) split
+        assert_eq!(suit_splits(&counts, 3), [SuitSplit { pair: None, melds: vec![] }]);
+        // Honours never form sequences
+        assert!(suit_splits(&tile_counts(&tiles_from_string("123z")), 3).is_empty());
+        // Five 2-man would otherwise share a key with a 1-man triplet
+        assert!(suit_splits(&tile_counts(&tiles_from_string("1122222m")), 0).is_empty());
+    }
+
+    #[test]
+    fn test_is_complete() {
+        assert!(is_complete(&tile_counts(&tiles_from_string("234456m66p123444s"))));
+        assert!(is_complete(&tile_counts(&tiles_from_string("11122233344455m"))));
+        assert!(is_complete(&tile_counts(&tiles_from_string("55z"))));
+        assert!(!is_complete(&tile_counts(&tiles_from_string("234456m66p12344s5z"))));
+        // Two pairs
+        assert!(!is_complete(&tile_counts(&tiles_from_string("123m11p123s11z"))));
+        // Chiitoitsu is not a standard hand
+        assert!(!is_complete(&tile_counts(&tiles_from_string("113344m55p22s3355z"))));
+    }
+
+    #[test]
+    fn test_meld_splits() {
+        assert_eq!(
+            meld_splits(&tile_counts(&tiles_from_string("123444m111z"))),
+            vec![vec![
+                tiles_from_string("123m"),
+                tiles_from_string("444m"),
+                tiles_from_string("111z")
+            ]]
+        );
+        assert!(meld_splits(&tile_counts(&tiles_from_string("12344m"))).is_empty());
+    }
+
+    #[test]
+    fn test_completing_tiles() {
+        assert_eq!(
+            completing_tiles(&tile_counts(&tiles_from_string("1112345678999m"))),
+            tiles_from_string("123456789m")
+        );
+        // 4-man is invalid since there are already 4 of them in the hand
+        assert_eq!(
+            completing_tiles(&tile_counts(&tiles_from_string("2344445678999m"))),
+            tiles_from_string("12356789m")
+        );
+        assert!(completing_tiles(&tile_counts(&tiles_from_string("1357m2468p1357s9m"))).is_empty());
+    }
+}
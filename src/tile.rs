@@ -1,6 +1,5 @@
 //! Provides a tile type and associated functions.
 
-use itertools::Itertools;
 use std::fmt;
 
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -63,6 +62,54 @@ impl Tile {
         Tile { suit: self.suit, number: self.number - 1 }
     }
 
+    /// Returns the dora indicated by this tile as a dora indicator.
+    ///
+    /// Numbers wrap from 9 to 1, winds from north to east and dragons from chun to haku. In sanma,
+    /// 1-man indicates 9-man, since 2-man to 8-man are removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::tile::tile_from_string;
+    ///
+    /// assert_eq!(tile_from_string("9p").dora(false), tile_from_string("1p"));
+    /// assert_eq!(tile_from_string("3z").dora(false), tile_from_string("4z"));
+    /// assert_eq!(tile_from_string("1m").dora(true), tile_from_string("9m"));
+    /// ```
+    pub fn dora(&self, sanma: bool) -> Tile {
+        let number = match self.number {
+            1 if sanma && self.is_man() => 9,
+            9 if self.is_number() => 1,
+            4 if self.is_wind() => 1,
+            7 if self.is_colour() => 5,
+            n => n + 1,
+        };
+        Tile { suit: self.suit, number }
+    }
+
+    /// Returns a unique index in `0..34`, following the tile ordering.
+    pub(crate) fn index(&self) -> usize {
+        let suit_offset = match self.suit {
+            Suit::Man => 0,
+            Suit::Pin => 9,
+            Suit::Sou => 18,
+            Suit::Hon => 27,
+        };
+        suit_offset + self.number as usize - 1
+    }
+
+    /// Returns the tile with the given index (see `Tile::index`).
+    pub(crate) fn from_index(index: usize) -> Tile {
+        let (suit, offset) = match index {
+            0..=8 => (Suit::Man, 0),
+            9..=17 => (Suit::Pin, 9),
+            18..=26 => (Suit::Sou, 18),
+            27..=33 => (Suit::Hon, 27),
+            _ => panic!("Invalid tile index: {}", index),
+        };
+        Tile { suit, number: (index - offset) as u8 + 1 }
+    }
+
     fn type_char(&self) -> char {
         match self.suit {
             Suit::Man => 'm',
@@ -197,16 +244,13 @@ pub(crate) fn with_tiles_removed(tiles: &[Tile], tiles_to_remove: &[Tile]) -> Ve
     result
 }
 
-/// Checks if `tiles` contains all tiles in `tiles_to_find`.
-pub(crate) fn has_tiles(tiles: &[Tile], tiles_to_find: &[Tile]) -> bool {
-    for tile in tiles_to_find.iter().sorted().dedup() {
-        let tiles_present = tiles.iter().filter(|&t| t == tile).count();
-        let tiles_needed = tiles_to_find.iter().filter(|&t| t == tile).count();
-        if tiles_present < tiles_needed {
-            return false;
-        }
+/// Counts the occurrences of each tile in `tiles`, indexed by `Tile::index`.
+pub(crate) fn tile_counts(tiles: &[Tile]) -> [u8; 34] {
+    let mut counts = [0; 34];
+    for tile in tiles {
+        counts[tile.index()] += 1;
     }
-    true
+    counts
 }
 
 #[cfg(test)]
@@ -311,6 +355,25 @@ mod tests {
     #[should_panic]
     fn test_tile_no_prev_colour() { t("5z").prev(); }
 
+    #[test]
+    fn test_tile_dora() {
+        assert_eq!(t("1m").dora(false), t("2m"));
+        assert_eq!(t("9s").dora(false), t("1s"));
+        assert_eq!(t("9m").dora(true), t("1m"));
+        assert_eq!(t("1m").dora(true), t("9m"));
+        assert_eq!(t("4z").dora(false), t("1z"));
+        assert_eq!(t("5z").dora(false), t("6z"));
+        assert_eq!(t("7z").dora(false), t("5z"));
+    }
+
+    #[test]
+    fn test_tile_index() {
+        for (i, tile) in all_tiles().iter().enumerate() {
+            assert_eq!(tile.index(), i);
+            assert_eq!(Tile::from_index(i), *tile);
+        }
+    }
+
     #[test]
     fn test_with_tiles_removed() {
         assert_eq!(with_tiles_removed(&ts("1m22p3s446z"), &ts("1m2p3s4z"),), ts("2p46z"),);
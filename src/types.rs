@@ -1,10 +1,12 @@
 //! Shared public types.
 
-use crate::tile::Tile;
+use crate::{custom_yaku::YakuRegistry, tile::Tile};
+#[cfg(feature = "serde")]
+use serde::Serialize;
 use std::fmt;
 
 /// Represents a call.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Call {
     pub ctype: CallType,
     // First tile is enough to determine the rest
@@ -21,6 +23,53 @@ pub enum CallType {
     NoCall,
 }
 
+/// Represents a meld (or pair) in a hand.
+///
+/// Melds are ordered by their tiles first.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Meld {
+    pub(crate) tiles: Vec<Tile>,
+    pub(crate) kind: MeldKind,
+    pub(crate) origin: MeldOrigin,
+}
+
+/// Represents a meld kind.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum MeldKind {
+    Sequence,
+    Triplet,
+    Quad,
+    Pair,
+}
+
+/// Represents how a meld was formed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum MeldOrigin {
+    /// Formed entirely from drawn tiles, including closed kans.
+    Concealed,
+    /// Formed by calling another player's discard.
+    Called,
+    /// Completed by the winning tile on ron.
+    Ron,
+}
+
+/// Represents the shape of the wait completed by the winning tile.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Wait {
+    /// Two-sided sequence wait (e.g. 34 waiting on 2 or 5).
+    Ryanmen,
+    /// Closed (middle) sequence wait (e.g. 35 waiting on 4).
+    Kanchan,
+    /// Edge sequence wait (e.g. 12 waiting on 3).
+    Penchan,
+    /// Dual pair wait, completing a triplet.
+    Shanpon,
+    /// Single tile wait, completing the pair (including chiitoitsu and single-wait kokushi).
+    Tanki,
+    /// Thirteen-sided kokushi musou wait.
+    Kokushi13,
+}
+
 /// Represents a fu reason.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub enum FuReason {
@@ -55,17 +104,32 @@ pub enum FuReason {
     // Misc
     RoundUp,
     NotWinning,
+    // Pair, sanma only
+    YakuhaiPairNorth,
 }
 
 /// Represents the union of yaku and yakuman.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum HanReason {
     Yaku(Yaku),
     Yakuman(Yakuman),
+    /// A user-defined yaku, by name (see `custom_yaku`).
+    CustomYaku(&'static str),
+    /// A user-defined yakuman, by name (see `custom_yaku`).
+    CustomYakuman(&'static str),
+}
+
+impl HanReason {
+    /// Whether this is a yakuman, built-in or user-defined.
+    pub fn is_yakuman(&self) -> bool {
+        matches!(self, HanReason::Yakuman(_) | HanReason::CustomYakuman(_))
+    }
 }
 
 /// Represents yaku.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Yaku {
     // 1 han, closed only
     MenzenTsumo,
@@ -110,10 +174,16 @@ pub enum Yaku {
     Dora,
     Akadora,
     Uradora,
+    Nukidora,
+    // Local yaku
+    Renhou,
+    Sanrenkou,
+    OpenRiichi,
 }
 
 /// Represents yakuman.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum Yakuman {
     KazoeYakuman,
     KokushiMusou,
@@ -129,9 +199,20 @@ pub enum Yakuman {
     Tenhou,
     Chiihou,
     NagashiMangan,
+    // Local yakuman
+    Daisharin,
+    IisouSuushun,
+    Shiisanpuutaa,
+    Daichisei,
+    Ishinouenimosannen,
+    // Local yakuman, see `Rules::open_riichi_deal_in_yakuman`
+    OpenRiichiDealIn,
 }
 
 /// Stores contextual information needed to score a hand.
+///
+/// `HandContext::builder` checks that the flags are consistent; contexts built directly can be
+/// checked with `HandContext::validate`.
 #[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
 pub struct HandContext {
     pub winning_tile: Tile,
@@ -147,14 +228,165 @@ pub struct HandContext {
     pub is_chankan: bool,
     pub is_tenhou: bool,
     pub is_chiihou: bool,
-    pub winning_meld: Vec<Tile>,
+    /// Whether a nondealer won by ron before their first draw (local yaku).
+    pub is_renhou: bool,
+    /// Whether riichi was declared with the hand exposed (local yaku). Requires `is_riichi` or
+    /// `is_double_riichi`.
+    pub is_open_riichi: bool,
+    /// Whether the winning tile was discarded by a player not in riichi. Used with
+    /// `Rules::open_riichi_deal_in_yakuman`.
+    pub is_non_riichi_deal_in: bool,
     pub n_dora: u8,
     pub n_akadora: u8,
     pub n_uradora: u8,
+    /// The number of north tiles set aside as bonus dora in sanma. These are not part of the hand.
+    pub n_nukidora: u8,
+    /// The dora indicators, used to count nukidora as dora when north is indicated. Dora in the
+    /// hand itself should be counted in `n_dora`.
+    pub dora_indicators: Vec<Tile>,
+    /// The uradora indicators, used like `dora_indicators`.
+    pub uradora_indicators: Vec<Tile>,
+    pub rules: Rules,
+}
+
+/// Represents rule options for scoring.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Rules {
+    /// Three-player rules, or `None` for four players.
+    pub sanma: Option<SanmaRules>,
+    /// Whether local yaku are counted: renhou, sanrenkou, open riichi, daisharin, iisou
+    /// suushun, shiisanpuutaa, daichisei and ishino uenimo sannen.
+    pub local_yaku: bool,
+    /// Whether an open riichi hand dealt into by a player not in riichi is yakuman. Only counted
+    /// with `local_yaku`.
+    pub open_riichi_deal_in_yakuman: bool,
+    /// Whether 3 han 60 fu and 4 han 30 fu hands are rounded up to mangan (kiriage mangan).
+    pub kiriage_mangan: bool,
+    /// The han and fu needed to reach each limit.
+    pub limits: LimitThresholds,
+    /// Whether hands are scored without limits (aotenjou). Each yakuman counts as 13 han on top
+    /// of the other yaku.
+    pub aotenjou: bool,
+    /// User-defined yaku, checked alongside the built-in yaku.
+    pub custom_yaku: YakuRegistry,
+    /// Whether a win is rejected when some of the hand's waits have no yaku (kataagari), even if
+    /// the winning tile has one. See `score::is_kataagari`.
+    pub reject_kataagari: bool,
+}
+
+impl Rules {
+    /// The limit thresholds, with kiriage mangan applied if enabled.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::types::Rules;
+    ///
+    /// let rules = Rules { kiriage_mangan: true, ..Default::default() };
+    /// assert_eq!(rules.limit_thresholds().mangan_3_han_fu, 60);
+    /// assert_eq!(rules.limit_thresholds().mangan_4_han_fu, 30);
+    /// ```
+    pub fn limit_thresholds(&self) -> LimitThresholds {
+        if self.kiriage_mangan {
+            let kiriage = LimitThresholds::kiriage();
+            LimitThresholds {
+                mangan_3_han_fu: self.limits.mangan_3_han_fu.min(kiriage.mangan_3_han_fu),
+                mangan_4_han_fu: self.limits.mangan_4_han_fu.min(kiriage.mangan_4_han_fu),
+                ..self.limits
+            }
+        } else {
+            self.limits
+        }
+    }
+}
+
+/// Represents the han and fu needed to reach each limit.
+///
+/// Hands of 5 han are always at least mangan. Hands with yakuman are always yakuman, whatever
+/// `kazoe_yakuman` is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct LimitThresholds {
+    /// The minimum fu for a 3 han hand to be mangan.
+    pub mangan_3_han_fu: u8,
+    /// The minimum fu for a 4 han hand to be mangan.
+    pub mangan_4_han_fu: u8,
+    /// The minimum han for haneman.
+    pub haneman: u8,
+    /// The minimum han for baiman.
+    pub baiman: u8,
+    /// The minimum han for sanbaiman.
+    pub sanbaiman: u8,
+    /// The minimum han for kazoe yakuman. Set above 13 to cap counted hands at sanbaiman.
+    pub kazoe_yakuman: u8,
+}
+
+impl Default for LimitThresholds {
+    fn default() -> Self {
+        LimitThresholds {
+            mangan_3_han_fu: 70,
+            mangan_4_han_fu: 40,
+            haneman: 6,
+            baiman: 8,
+            sanbaiman: 11,
+            kazoe_yakuman: 13,
+        }
+    }
+}
+
+impl LimitThresholds {
+    /// The thresholds under kiriage mangan: 3 han 60 fu and 4 han 30 fu are mangan.
+    pub fn kiriage() -> Self {
+        LimitThresholds { mangan_3_han_fu: 60, mangan_4_han_fu: 30, ..Default::default() }
+    }
+}
+
+/// Represents three-player (sanma) rule options.
+///
+/// In sanma, the 2-man to 8-man tiles are removed and chi is not allowed. The north seat is
+/// absent, so the east, south and west seats play.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SanmaRules {
+    pub north: NorthRule,
+    pub payment: SanmaPayment,
+}
+
+/// Represents how north (pei) tiles are treated in sanma.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum NorthRule {
+    /// North tiles may be set aside as bonus dora (nukidora).
+    #[default]
+    Nukidora,
+    /// A north triplet is always yakuhai.
+    Yakuhai,
+}
+
+/// Represents how a win by tsumo is paid in sanma.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum SanmaPayment {
+    /// Tsumo loss (tsumo-son): the absent north seat's share is not paid.
+    #[default]
+    TsumoLoss,
+    /// North bisection: the absent north seat's share is split evenly between the payers, with
+    /// each half rounded up to the nearest 100.
+    NorthBisection,
+    /// Tsumo loss-free: the payments add up to the ron value, split from the base points before
+    /// rounding.
+    TsumoLossFree,
+}
+
+/// Represents one way of interpreting a complete hand.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Interpretation {
+    /// Four melds (including calls) and a pair.
+    Standard { pair: Tile, melds: Vec<Meld> },
+    /// Seven distinct pairs.
+    Chiitoitsu { pairs: Vec<Tile> },
+    /// One of each terminal and honour, with one of them paired.
+    Kokushi { pair: Tile },
 }
 
 /// Represents a scoring limit.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Limit {
     NoLimit,
     Mangan,
@@ -164,129 +396,77 @@ pub enum Limit {
     Yakuman,
 }
 
+/// Represents a language for names of yaku, fu reasons, waits and limits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Locale {
+    /// Romanised Japanese, e.g. "Tanyao". `HanReason` and `Wait` are displayed this way.
+    Romaji,
+    /// English, e.g. "All simples". `FuReason` and `Limit` are displayed this way.
+    English,
+    /// Japanese, e.g. "断幺九".
+    Japanese,
+}
+
+/// Represents the level of detail of a score breakdown.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BreakdownStyle {
+    /// A single line.
+    Compact,
+    /// One line per yaku and fu reason.
+    Verbose,
+}
+
+/// Represents the output format of a score breakdown.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BreakdownFormat {
+    Text,
+    Markdown,
+}
+
 /// Represents an allocation of points.
 #[derive(Debug, Eq, PartialEq)]
 pub enum Points {
     NoPoints,
     /// For dealer tsumo: the same value is paid by each nondealer.
-    TsumoAll(u16),
+    TsumoAll(u64),
     /// For nondealer tsumo: the first value is paid by each nondealer and the second value is
     /// paid by the dealer.
-    Tsumo(u16, u16),
+    Tsumo(u64, u64),
     /// For ron: the single value is paid in full by the discarder.
-    Ron(u16),
+    Ron(u64),
 }
 
 impl fmt::Display for FuReason {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                FuReason::Base => "Base",
-                FuReason::Chiitoitsu => "Chiitoitsu",
-                FuReason::ClosedRon => "Closed ron",
-                FuReason::TsumoNoPinfu => "Tsumo, no pinfu",
-                FuReason::OpenPinfu => "Open pinfu",
-                FuReason::YakuhaiPairColours => "Yakuhai pair (colours)",
-                FuReason::YakuhaiPairRoundWind => "Yakuhai pair (round wind)",
-                FuReason::YakuhaiPairPlayerWind => "Yakuhai pair (player wind)",
-                FuReason::OpenTripletHonours => "Open triplet (honours)",
-                FuReason::OpenTripletTerminals => "Open triplet (terminals)",
-                FuReason::OpenTripletSimples => "Open triplet (simples)",
-                FuReason::ClosedTripletHonours => "Closed triplet (honours)",
-                FuReason::ClosedTripletTerminals => "Closed triplet (terminals)",
-                FuReason::ClosedTripletSimples => "Closed triplet (simples)",
-                FuReason::OpenQuadHonours => "Open quad (honours)",
-                FuReason::OpenQuadTerminals => "Open quad (terminals)",
-                FuReason::OpenQuadSimples => "Open quad (simples)",
-                FuReason::ClosedQuadHonours => "Closed quad (honours)",
-                FuReason::ClosedQuadTerminals => "Closed quad (terminals)",
-                FuReason::ClosedQuadSimples => "Closed quad (simples)",
-                FuReason::Kanchan => "Kanchan wait",
-                FuReason::Penchan => "Penchan wait",
-                FuReason::Tanki => "Tanki wait",
-                FuReason::RoundUp => "Round up",
-                FuReason::NotWinning => "Not winning",
-            }
-        )
+        write!(f, "{}", self.name(Locale::English))
     }
 }
 
 impl fmt::Display for HanReason {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                HanReason::Yaku(Yaku::MenzenTsumo) => "Menzen tsumo",
-                HanReason::Yaku(Yaku::Riichi) => "Riichi",
-                HanReason::Yaku(Yaku::Ippatsu) => "Ippatsu",
-                HanReason::Yaku(Yaku::Pinfu) => "Pinfu",
-                HanReason::Yaku(Yaku::Iipeikou) => "Iipeikou",
-                HanReason::Yaku(Yaku::HaiteiRaoyue) => "Haitei raoyue",
-                HanReason::Yaku(Yaku::HouteiRaoyui) => "Houtei raoyui",
-                HanReason::Yaku(Yaku::RinshanKaihou) => "Rinshan kaihou",
-                HanReason::Yaku(Yaku::Chankan) => "Chankan",
-                HanReason::Yaku(Yaku::Tanyao) => "Tanyao",
-                HanReason::Yaku(Yaku::Ton) => "Ton",
-                HanReason::Yaku(Yaku::Nan) => "Nan",
-                HanReason::Yaku(Yaku::Sha) => "Sha",
-                HanReason::Yaku(Yaku::Pei) => "Pei",
-                HanReason::Yaku(Yaku::Haku) => "Haku",
-                HanReason::Yaku(Yaku::Hatsu) => "Hatsu",
-                HanReason::Yaku(Yaku::Chun) => "Chun",
-                HanReason::Yaku(Yaku::DoubleRiichi) => "Double riichi",
-                HanReason::Yaku(Yaku::Chantaiyao) => "Chanta",
-                HanReason::Yaku(Yaku::SanshokuDoujun) => "Sanshoku doujun",
-                HanReason::Yaku(Yaku::Sankantsu) => "Sankantsu",
-                HanReason::Yaku(Yaku::Ittsu) => "Ittsu",
-                HanReason::Yaku(Yaku::Toitoi) => "Toitoi",
-                HanReason::Yaku(Yaku::Sanankou) => "Sanankou",
-                HanReason::Yaku(Yaku::SanshokuDoukou) => "Sanshoku doukou",
-                HanReason::Yaku(Yaku::Chiitoitsu) => "Chiitoitsu",
-                HanReason::Yaku(Yaku::Honroutou) => "Honroutou",
-                HanReason::Yaku(Yaku::Shousangen) => "Shousangen",
-                HanReason::Yaku(Yaku::Ryanpeikou) => "Ryanpeikou",
-                HanReason::Yaku(Yaku::Honitsu) => "Honitsu",
-                HanReason::Yaku(Yaku::JunchanTaiyao) => "Junchan",
-                HanReason::Yaku(Yaku::Chinitsu) => "Chinitsu",
-                HanReason::Yaku(Yaku::Dora) => "Dora",
-                HanReason::Yaku(Yaku::Akadora) => "Akadora",
-                HanReason::Yaku(Yaku::Uradora) => "Uradora",
-
-                HanReason::Yakuman(Yakuman::KazoeYakuman) => "Kazoe yakuman",
-                HanReason::Yakuman(Yakuman::KokushiMusou) => "Kokushi musou",
-                HanReason::Yakuman(Yakuman::Suuankou) => "Suuankou",
-                HanReason::Yakuman(Yakuman::Daisangen) => "Daisangen",
-                HanReason::Yakuman(Yakuman::Shousuushii) => "Shousuushii",
-                HanReason::Yakuman(Yakuman::Daisuushii) => "Daisuushii",
-                HanReason::Yakuman(Yakuman::Tsuuiisou) => "Tsuuiisou",
-                HanReason::Yakuman(Yakuman::Chinroutou) => "Chinroutou",
-                HanReason::Yakuman(Yakuman::Ryuuiisou) => "Ryuuiisou",
-                HanReason::Yakuman(Yakuman::ChuurenPoutou) => "Chuuren poutou",
-                HanReason::Yakuman(Yakuman::Suukantsu) => "Suukantsu",
-                HanReason::Yakuman(Yakuman::Tenhou) => "Tenhou",
-                HanReason::Yakuman(Yakuman::Chiihou) => "Chiihou",
-                HanReason::Yakuman(Yakuman::NagashiMangan) => "Nagashi mangan",
-            }
-        )
+        write!(f, "{}", self.name(Locale::Romaji))
+    }
+}
+
+impl fmt::Display for Wait {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name(Locale::Romaji))
+    }
+}
+
+impl fmt::Display for Points {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Points::NoPoints => write!(f, "No points"),
+            Points::TsumoAll(a) => write!(f, "{} all", a),
+            Points::Tsumo(a, b) => write!(f, "{}/{}", a, b),
+            Points::Ron(a) => write!(f, "{}", a),
+        }
     }
 }
 
 impl fmt::Display for Limit {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Limit::NoLimit => "No limit",
-                Limit::Mangan => "Mangan",
-                Limit::Haneman => "Haneman",
-                Limit::Baiman => "Baiman",
-                Limit::Sanbaiman => "Sanbaiman",
-                Limit::Yakuman => "Yakuman",
-            }
-        )
+        write!(f, "{}", self.name(Locale::English))
     }
 }
@@ -1,21 +1,27 @@
-use crate::{division::Division, tile::Tile, types::Call};
+use crate::{
+    division::Division,
+    tile::Tile,
+    types::{Call, Meld},
+};
 
-pub(crate) fn combine_melds(division: &Division, calls: &Vec<Call>) -> Vec<Vec<Tile>> {
+pub(crate) fn combine_melds(division: &Division, calls: &Vec<Call>) -> Vec<Meld> {
     let mut melds = division.melds.clone();
     melds.append(&mut calls.iter().map(|call| call.meld()).collect());
     melds
 }
 
+/// Like `combine_melds`, but as lists of tiles and with the pair at the end.
 pub(crate) fn combine_melds_with_pair(division: &Division, calls: &Vec<Call>) -> Vec<Vec<Tile>> {
-    let mut melds = combine_melds(division, calls);
+    let mut melds: Vec<Vec<Tile>> =
+        combine_melds(division, calls).iter().map(|m| m.tiles().to_vec()).collect();
     melds.push(division.pair.clone());
     melds
 }
 
 pub(crate) fn flatten_tiles(division: &Division, calls: &Vec<Call>) -> Vec<Tile> {
     let mut tiles = division.pair.clone();
-    tiles.append(&mut division.melds.iter().flatten().cloned().collect());
+    tiles.append(&mut division.melds.iter().flat_map(|m| m.tiles()).cloned().collect());
     tiles.append(&mut division.remaining.clone());
-    tiles.append(&mut calls.iter().flat_map(|call| call.meld()).collect());
+    tiles.append(&mut calls.iter().flat_map(|call| call.tiles()).collect());
     tiles
 }
@@ -0,0 +1,284 @@
+//! The wall and dead wall, for simulating a hand.
+//!
+//! Shuffles are deterministic for a given seed, so hands can be replayed.
+
+use crate::tile::{all_tiles, Tile};
+use std::collections::VecDeque;
+
+const DEAD_WALL_SIZE: usize = 14;
+const N_RINSHAN: usize = 4;
+const MAX_DORA_INDICATORS: usize = 5;
+
+/// Represents a physical tile, which may be a red five.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WallTile {
+    pub tile: Tile,
+    pub is_red: bool,
+}
+
+/// Represents wall composition options.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WallRules {
+    /// Whether to use the three-player set, without 2-man to 8-man.
+    pub sanma: bool,
+    /// The number of red fives in man, pin and sou. Red 5-man are ignored in sanma.
+    pub red_fives: [u8; 3],
+}
+
+impl Default for WallRules {
+    fn default() -> WallRules { WallRules { sanma: false, red_fives: [1, 1, 1] } }
+}
+
+/// Represents the wall during a hand.
+///
+/// The last 14 tiles form the dead wall: 4 replacement (rinshan) tiles, then 5 dora indicators
+/// each paired with an uradora indicator. Each replacement draw moves the last live tile into the
+/// dead wall, so the dead wall keeps 14 tiles.
+#[derive(Clone, Debug)]
+pub struct Wall {
+    live: VecDeque<WallTile>,
+    rinshan: VecDeque<WallTile>,
+    dora_indicators: Vec<WallTile>,
+    uradora_indicators: Vec<WallTile>,
+    n_revealed: usize,
+    n_seats: usize,
+    last_draw_rinshan: bool,
+}
+
+impl Wall {
+    /// Builds and shuffles a wall, revealing the first dora indicator.
+    ///
+    /// Panics if `rules.red_fives` has more than four red fives in a suit.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use toitoi::wall::{Wall, WallRules};
+    ///
+    /// let mut wall = Wall::new(42, WallRules::default());
+    /// let hands = wall.deal();
+    ///
+    /// assert_eq!(hands.len(), 4);
+    /// assert!(hands.iter().all(|hand| hand.len() == 13));
+    /// assert_eq!(wall.tiles_left(), 136 - 14 - 4 * 13);
+    /// assert_eq!(wall.dora_indicators().len(), 1);
+    ///
+    /// // The same seed gives the same wall
+    /// assert_eq!(Wall::new(42, WallRules::default()).deal(), hands);
+    /// ```
+    pub fn new(seed: u64, rules: WallRules) -> Wall {
+        assert!(rules.red_fives.iter().all(|&n| n <= 4), "At most four red fives per suit");
+        let mut tiles = tile_set(rules);
+        shuffle(&mut tiles, seed);
+
+        let mut dead = tiles.split_off(tiles.len() - DEAD_WALL_SIZE);
+        let indicators = dead.split_off(N_RINSHAN);
+        let (dora_indicators, uradora_indicators) =
+            indicators.chunks(2).map(|pair| (pair[0], pair[1])).unzip();
+        Wall {
+            live: tiles.into(),
+            rinshan: dead.into(),
+            dora_indicators,
+            uradora_indicators,
+            n_revealed: 1,
+            n_seats: if rules.sanma { 3 } else { 4 },
+            last_draw_rinshan: false,
+        }
+    }
+
+    /// Deals 13 tiles to each seat, four at a time and then one each. The hands are indexed by
+    /// seat, from 0 (east).
+    ///
+    /// Panics if tiles have already been drawn.
+    pub fn deal(&mut self) -> Vec<Vec<WallTile>> {
+        assert_eq!(self.live.len(), self.n_tiles_live(), "Tiles have already been drawn");
+        let mut hands: Vec<Vec<WallTile>> = vec![vec![]; self.n_seats];
+        for n in [4, 4, 4, 1].iter() {
+            for hand in hands.iter_mut() {
+                hand.extend(self.live.drain(..n));
+            }
+        }
+        hands
+    }
+
+    /// Draws the next tile from the live wall, or `None` if it is exhausted.
+    pub fn draw(&mut self) -> Option<WallTile> {
+        let tile = self.live.pop_front()?;
+        self.last_draw_rinshan = false;
+        Some(tile)
+    }
+
+    /// Draws a replacement tile from the dead wall after a quad (or nukidora in sanma), or `None`
+    /// if the live wall is exhausted.
+    pub fn draw_rinshan(&mut self) -> Option<WallTile> {
+        let replenish = self.live.pop_back()?;
+        self.rinshan.push_back(replenish);
+        self.last_draw_rinshan = true;
+        self.rinshan.pop_front()
+    }
+
+    /// Reveals the next dora indicator after a quad, or `None` if all five are revealed.
+    pub fn reveal_kan_dora(&mut self) -> Option<Tile> {
+        if self.n_revealed == MAX_DORA_INDICATORS {
+            return None;
+        }
+        self.n_revealed += 1;
+        Some(self.dora_indicators[self.n_revealed - 1].tile)
+    }
+
+    /// The revealed dora indicators, for `HandContext::dora_indicators`.
+    pub fn dora_indicators(&self) -> Vec<Tile> {
+        self.dora_indicators[..self.n_revealed].iter().map(|t| t.tile).collect()
+    }
+
+    /// The uradora indicators under the revealed dora indicators, for
+    /// `HandContext::uradora_indicators`.
+    pub fn uradora_indicators(&self) -> Vec<Tile> {
+        self.uradora_indicators[..self.n_revealed].iter().map(|t| t.tile).collect()
+    }
+
+    /// The number of tiles left to draw from the live wall.
+    pub fn tiles_left(&self) -> usize { self.live.len() }
+
+    /// Whether the last draw took the last tile of the live wall, for `HandContext::is_haitei`.
+    ///
+    /// Replacement draws are never haitei.
+    pub fn is_haitei(&self) -> bool { self.live.is_empty() && !self.last_draw_rinshan }
+
+    /// Whether a discard now would be the last of the hand, for `HandContext::is_houtei`.
+    pub fn is_houtei(&self) -> bool { self.live.is_empty() }
+
+    fn n_tiles_live(&self) -> usize {
+        let n_tiles = if self.n_seats == 3 { 108 } else { 136 };
+        n_tiles - DEAD_WALL_SIZE
+    }
+}
+
+/// Counts the red fives in a hand, for `HandContext::n_akadora`.
+pub fn n_red_fives(tiles: &[WallTile]) -> u8 { tiles.iter().filter(|t| t.is_red).count() as u8 }
+
+// Four of each tile, with the red fives replacing regular fives
+fn tile_set(rules: WallRules) -> Vec<WallTile> {
+    let mut tiles = vec![];
+    for tile in all_tiles() {
+        if rules.sanma && tile.is_man() && !tile.is_terminal() {
+            continue;
+        }
+        let n_red = match tile.number() {
+            5 if tile.is_man() => rules.red_fives[0],
+            5 if tile.is_pin() => rules.red_fives[1],
+            5 if tile.is_sou() => rules.red_fives[2],
+            _ => 0,
+        };
+        for i in 0..4 {
+            tiles.push(WallTile { tile, is_red: i < n_red });
+        }
+    }
+    tiles
+}
+
+// Fisher-Yates shuffle driven by SplitMix64
+fn shuffle(tiles: &mut [WallTile], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    for i in (1..tiles.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        tiles.swap(i, j);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tile::{tile_counts, tile_from_string};
+
+    fn tiles(wall_tiles: &[WallTile]) -> Vec<Tile> { wall_tiles.iter().map(|t| t.tile).collect() }
+
+    #[test]
+    fn test_tile_set() {
+        let set = tile_set(WallRules::default());
+        assert_eq!(set.len(), 136);
+        assert!(tile_counts(&tiles(&set)).iter().all(|&c| c == 4));
+        assert_eq!(n_red_fives(&set), 3);
+
+        let set = tile_set(WallRules { sanma: true, red_fives: [1, 2, 2] });
+        assert_eq!(set.len(), 108);
+        assert_eq!(tile_counts(&tiles(&set))[tile_from_string("5m").index()], 0);
+        assert_eq!(n_red_fives(&set), 4);
+    }
+
+    #[test]
+    fn test_shuffle_seeded() {
+        let draw_all = |seed| {
+            let mut wall = Wall::new(seed, WallRules::default());
+            std::iter::from_fn(|| wall.draw()).collect::<Vec<WallTile>>()
+        };
+        assert_eq!(draw_all(1), draw_all(1));
+        assert_ne!(draw_all(1), draw_all(2));
+        assert_eq!(draw_all(1).len(), 122);
+    }
+
+    #[test]
+    fn test_deal_sanma() {
+        let mut wall = Wall::new(7, WallRules { sanma: true, ..Default::default() });
+        let hands = wall.deal();
+        assert_eq!(hands.len(), 3);
+        assert_eq!(wall.tiles_left(), 108 - 14 - 3 * 13);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_many_red_fives() {
+        Wall::new(0, WallRules { red_fives: [0, 5, 0], ..Default::default() });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_deal_twice() {
+        let mut wall = Wall::new(7, WallRules::default());
+        wall.deal();
+        wall.deal();
+    }
+
+    #[test]
+    fn test_rinshan_and_kan_dora() {
+        let mut wall = Wall::new(3, WallRules::default());
+        let before = wall.clone();
+        let rinshan = wall.draw_rinshan().unwrap();
+
+        assert_eq!(rinshan, before.rinshan[0]);
+        assert_eq!(wall.tiles_left(), before.tiles_left() - 1);
+        assert_eq!(wall.rinshan.len(), N_RINSHAN);
+
+        for n in 2..=5 {
+            assert_eq!(wall.reveal_kan_dora(), Some(before.dora_indicators[n - 1].tile));
+            assert_eq!(wall.dora_indicators().len(), n);
+            assert_eq!(wall.uradora_indicators().len(), n);
+        }
+        assert_eq!(wall.reveal_kan_dora(), None);
+    }
+
+    #[test]
+    fn test_haitei_and_houtei() {
+        let mut wall = Wall::new(5, WallRules::default());
+        while wall.tiles_left() > 1 {
+            wall.draw();
+        }
+        assert!(!wall.is_haitei() && !wall.is_houtei());
+        wall.draw_rinshan();
+        // A replacement draw for the last tile is not haitei, but its discard is houtei
+        assert!(!wall.is_haitei() && wall.is_houtei());
+        assert_eq!(wall.draw(), None);
+        assert_eq!(wall.draw_rinshan(), None);
+
+        let mut wall = Wall::new(5, WallRules::default());
+        while wall.draw().is_some() {}
+        assert!(wall.is_haitei() && wall.is_houtei());
+    }
+}
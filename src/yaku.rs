@@ -2,9 +2,9 @@
 
 use crate::{
     division::Division,
-    meld::{is_quadruplet, is_sequence, is_triplet, no_open_calls, Meld},
+    meld::no_open_calls,
     tile::{tile_from_string, tiles_from_string, Tile},
-    types::{Call, CallType, HandContext, Yaku, Yakuman},
+    types::{Call, CallType, HandContext, MeldKind, NorthRule, SanmaRules, Yaku, Yakuman},
     utils::{combine_melds, combine_melds_with_pair, flatten_tiles},
 };
 use itertools::Itertools;
@@ -19,6 +19,73 @@ pub(crate) struct YakuInfo<T> {
     supercedes: Vec<T>,
 }
 
+// Every yaku, in declaration order
+pub(crate) const ALL_YAKU: [Yaku; 39] = [
+    Yaku::MenzenTsumo,
+    Yaku::Riichi,
+    Yaku::Ippatsu,
+    Yaku::Pinfu,
+    Yaku::Iipeikou,
+    Yaku::HaiteiRaoyue,
+    Yaku::HouteiRaoyui,
+    Yaku::RinshanKaihou,
+    Yaku::Chankan,
+    Yaku::Tanyao,
+    Yaku::Ton,
+    Yaku::Nan,
+    Yaku::Sha,
+    Yaku::Pei,
+    Yaku::Haku,
+    Yaku::Hatsu,
+    Yaku::Chun,
+    Yaku::DoubleRiichi,
+    Yaku::Chantaiyao,
+    Yaku::SanshokuDoujun,
+    Yaku::Sankantsu,
+    Yaku::Ittsu,
+    Yaku::Toitoi,
+    Yaku::Sanankou,
+    Yaku::SanshokuDoukou,
+    Yaku::Chiitoitsu,
+    Yaku::Honroutou,
+    Yaku::Shousangen,
+    Yaku::Ryanpeikou,
+    Yaku::Honitsu,
+    Yaku::JunchanTaiyao,
+    Yaku::Chinitsu,
+    Yaku::Dora,
+    Yaku::Akadora,
+    Yaku::Uradora,
+    Yaku::Nukidora,
+    Yaku::Renhou,
+    Yaku::Sanrenkou,
+    Yaku::OpenRiichi,
+];
+
+// Every yakuman, in declaration order
+pub(crate) const ALL_YAKUMAN: [Yakuman; 20] = [
+    Yakuman::KazoeYakuman,
+    Yakuman::KokushiMusou,
+    Yakuman::Suuankou,
+    Yakuman::Daisangen,
+    Yakuman::Shousuushii,
+    Yakuman::Daisuushii,
+    Yakuman::Tsuuiisou,
+    Yakuman::Chinroutou,
+    Yakuman::Ryuuiisou,
+    Yakuman::ChuurenPoutou,
+    Yakuman::Suukantsu,
+    Yakuman::Tenhou,
+    Yakuman::Chiihou,
+    Yakuman::NagashiMangan,
+    Yakuman::Daisharin,
+    Yakuman::IisouSuushun,
+    Yakuman::Shiisanpuutaa,
+    Yakuman::Daichisei,
+    Yakuman::Ishinouenimosannen,
+    Yakuman::OpenRiichiDealIn,
+];
+
 // Excluding pinfu and doras
 const YAKU_TO_CHECK: [Yaku; 31] = [
     Yaku::MenzenTsumo,
@@ -54,6 +121,8 @@ const YAKU_TO_CHECK: [Yaku; 31] = [
     Yaku::Chinitsu,
 ];
 
+const LOCAL_YAKU_TO_CHECK: [Yaku; 3] = [Yaku::Renhou, Yaku::Sanrenkou, Yaku::OpenRiichi];
+
 // Excluding kazoe yakuman, nagashi mangan
 const YAKUMAN_TO_CHECK: [Yakuman; 12] = [
     Yakuman::KokushiMusou,
@@ -70,15 +139,49 @@ const YAKUMAN_TO_CHECK: [Yakuman; 12] = [
     Yakuman::Chiihou,
 ];
 
+// Excluding shiisanpuutaa, which is checked separately
+const LOCAL_YAKUMAN_TO_CHECK: [Yakuman; 5] = [
+    Yakuman::Daisharin,
+    Yakuman::IisouSuushun,
+    Yakuman::Daichisei,
+    Yakuman::Ishinouenimosannen,
+    Yakuman::OpenRiichiDealIn,
+];
+
 impl Yaku {
     pub(crate) fn han_closed(&self) -> u8 { self.info().han_closed }
 
     pub(crate) fn han_open(&self) -> u8 { self.info().han_open }
 
+    pub(crate) fn supercedes(&self) -> Vec<Yaku> { self.info().supercedes }
+
+    pub(crate) fn is_local(&self) -> bool { LOCAL_YAKU_TO_CHECK.contains(self) }
+
     pub(crate) fn is_wind(&self) -> bool {
         *self == Yaku::Ton || *self == Yaku::Nan || *self == Yaku::Sha || *self == Yaku::Pei
     }
 
+    // Whether the yaku depends only on the hand context, not the hand composition
+    pub(crate) fn is_contextual(&self) -> bool {
+        matches!(
+            self,
+            Yaku::MenzenTsumo
+                | Yaku::Riichi
+                | Yaku::Ippatsu
+                | Yaku::OpenRiichi
+                | Yaku::HaiteiRaoyue
+                | Yaku::HouteiRaoyui
+                | Yaku::RinshanKaihou
+                | Yaku::Chankan
+                | Yaku::DoubleRiichi
+                | Yaku::Renhou
+                | Yaku::Dora
+                | Yaku::Akadora
+                | Yaku::Uradora
+                | Yaku::Nukidora
+        )
+    }
+
     fn make_info(
         han_closed: u8, han_open: u8, check_func: CheckFunc, supercedes: Vec<Yaku>,
     ) -> YakuInfo<Yaku> {
@@ -91,6 +194,17 @@ impl Yakuman {
 
     pub(crate) fn han_open(&self) -> u8 { self.info().han_open }
 
+    pub(crate) fn supercedes(&self) -> Vec<Yakuman> { self.info().supercedes }
+
+    pub(crate) fn is_local(&self) -> bool {
+        *self == Yakuman::Shiisanpuutaa || LOCAL_YAKUMAN_TO_CHECK.contains(self)
+    }
+
+    // Whether the yakuman depends only on the hand context, not the hand composition
+    pub(crate) fn is_contextual(&self) -> bool {
+        matches!(self, Yakuman::Tenhou | Yakuman::Chiihou | Yakuman::OpenRiichiDealIn)
+    }
+
     fn make_info(
         han_closed: u8, han_open: u8, check_func: CheckFunc, supercedes: Vec<Yakuman>,
     ) -> YakuInfo<Yakuman> {
@@ -109,6 +223,7 @@ impl Checkable<Yaku> for Yaku {
             Yaku::MenzenTsumo => Yaku::make_info(1, 0, has_menzen_tsumo, vec![]),
             Yaku::Riichi => Yaku::make_info(1, 0, has_riichi, vec![]),
             Yaku::Ippatsu => Yaku::make_info(1, 0, has_ippatsu, vec![]),
+            Yaku::OpenRiichi => Yaku::make_info(1, 0, has_open_riichi, vec![]),
             Yaku::Pinfu => Yaku::make_info(1, 0, placeholder, vec![]),
             Yaku::Iipeikou => Yaku::make_info(1, 0, has_iipeikou, vec![]),
             Yaku::HaiteiRaoyue => Yaku::make_info(1, 1, has_haitei, vec![]),
@@ -123,7 +238,7 @@ impl Checkable<Yaku> for Yaku {
             Yaku::Haku => Yaku::make_info(1, 1, has_haku, vec![]),
             Yaku::Hatsu => Yaku::make_info(1, 1, has_hatsu, vec![]),
             Yaku::Chun => Yaku::make_info(1, 1, has_chun, vec![]),
-            Yaku::DoubleRiichi => Yaku::make_info(2, 0, has_double_riichi, vec![]),
+            Yaku::DoubleRiichi => Yaku::make_info(2, 0, has_double_riichi, vec![Yaku::Riichi]),
             Yaku::Chantaiyao => Yaku::make_info(2, 1, has_chanta, vec![]),
             Yaku::SanshokuDoujun => Yaku::make_info(2, 1, has_sanshoku_doujun, vec![]),
             Yaku::Ittsu => Yaku::make_info(2, 1, has_ittsu, vec![]),
@@ -145,7 +260,14 @@ impl Checkable<Yaku> for Yaku {
             Yaku::Chinitsu => {
                 Yaku::make_info(6, 5, has_chinitsu, vec![Yaku::Honitsu, Yaku::JunchanTaiyao])
             }
-            _ => panic!("Should get yaku info from elsewhere"),
+            // Mangan, in place of the yaku for winning by ron
+            Yaku::Renhou => Yaku::make_info(5, 0, has_renhou, vec![]),
+            Yaku::Sanrenkou => Yaku::make_info(2, 2, has_sanrenkou, vec![]),
+            // Counted from the hand context, 1 han each
+            Yaku::Dora => Yaku::make_info(1, 1, placeholder, vec![]),
+            Yaku::Akadora => Yaku::make_info(1, 1, placeholder, vec![]),
+            Yaku::Uradora => Yaku::make_info(1, 0, placeholder, vec![]),
+            Yaku::Nukidora => Yaku::make_info(1, 1, placeholder, vec![]),
         }
     }
 
@@ -157,8 +279,10 @@ impl Checkable<Yaku> for Yaku {
 impl Checkable<Yakuman> for Yakuman {
     fn info(&self) -> YakuInfo<Yakuman> {
         match self {
-            Yakuman::KokushiMusou => Yakuman::make_info(13, 13, has_kokushi, vec![]),
-            Yakuman::Suuankou => Yakuman::make_info(13, 13, has_suuankou, vec![]),
+            // Not checked, as hands are counted up to yakuman
+            Yakuman::KazoeYakuman => Yakuman::make_info(13, 13, placeholder, vec![]),
+            Yakuman::KokushiMusou => Yakuman::make_info(13, 0, has_kokushi, vec![]),
+            Yakuman::Suuankou => Yakuman::make_info(13, 0, has_suuankou, vec![]),
             Yakuman::Daisangen => Yakuman::make_info(13, 13, has_daisangen, vec![]),
             Yakuman::Shousuushii => Yakuman::make_info(13, 13, has_shousuushii, vec![]),
             Yakuman::Daisuushii => {
@@ -167,11 +291,22 @@ impl Checkable<Yakuman> for Yakuman {
             Yakuman::Tsuuiisou => Yakuman::make_info(13, 13, has_tsuuiisou, vec![]),
             Yakuman::Chinroutou => Yakuman::make_info(13, 13, has_chinroutou, vec![]),
             Yakuman::Ryuuiisou => Yakuman::make_info(13, 13, has_ryuuiisou, vec![]),
-            Yakuman::ChuurenPoutou => Yakuman::make_info(13, 13, has_chuuren, vec![]),
+            Yakuman::ChuurenPoutou => Yakuman::make_info(13, 0, has_chuuren, vec![]),
             Yakuman::Suukantsu => Yakuman::make_info(13, 13, has_suukantsu, vec![]),
-            Yakuman::Tenhou => Yakuman::make_info(13, 13, has_tenhou, vec![]),
-            Yakuman::Chiihou => Yakuman::make_info(13, 13, has_chiihou, vec![]),
-            _ => panic!("Should get yakuman info from elsewhere"),
+            Yakuman::Tenhou => Yakuman::make_info(13, 0, has_tenhou, vec![]),
+            Yakuman::Chiihou => Yakuman::make_info(13, 0, has_chiihou, vec![]),
+            // Not checked, as it is settled as mangan at an exhaustive draw
+            Yakuman::NagashiMangan => Yakuman::make_info(5, 5, placeholder, vec![]),
+            Yakuman::OpenRiichiDealIn => Yakuman::make_info(13, 0, has_open_riichi_deal_in, vec![]),
+            Yakuman::Daisharin => Yakuman::make_info(13, 0, has_daisharin, vec![]),
+            Yakuman::IisouSuushun => Yakuman::make_info(13, 0, has_iisou_suushun, vec![]),
+            Yakuman::Shiisanpuutaa => Yakuman::make_info(13, 0, has_shiisanpuutaa, vec![]),
+            Yakuman::Daichisei => {
+                Yakuman::make_info(13, 0, has_daichisei, vec![Yakuman::Tsuuiisou])
+            }
+            Yakuman::Ishinouenimosannen => {
+                Yakuman::make_info(13, 0, has_ishinouenimosannen, vec![])
+            }
         }
     }
 
@@ -184,14 +319,26 @@ impl Checkable<Yakuman> for Yakuman {
 pub(crate) fn yaku_in_hand(
     division: &Division, calls: &Vec<Call>, context: &HandContext,
 ) -> Vec<Yaku> {
-    _find_in_hand(&YAKU_TO_CHECK, division, calls, context)
+    if context.rules.local_yaku {
+        let to_check: Vec<Yaku> =
+            YAKU_TO_CHECK.iter().chain(&LOCAL_YAKU_TO_CHECK).cloned().collect();
+        _find_in_hand(&to_check, division, calls, context)
+    } else {
+        _find_in_hand(&YAKU_TO_CHECK, division, calls, context)
+    }
 }
 
 /// Finds all yakuman in the given hand.
 pub(crate) fn yakuman_in_hand(
     division: &Division, calls: &Vec<Call>, context: &HandContext,
 ) -> Vec<Yakuman> {
-    _find_in_hand(&YAKUMAN_TO_CHECK, division, calls, context)
+    if context.rules.local_yaku {
+        let to_check: Vec<Yakuman> =
+            YAKUMAN_TO_CHECK.iter().chain(&LOCAL_YAKUMAN_TO_CHECK).cloned().collect();
+        _find_in_hand(&to_check, division, calls, context)
+    } else {
+        _find_in_hand(&YAKUMAN_TO_CHECK, division, calls, context)
+    }
 }
 
 fn _find_in_hand<T: Checkable<T> + Clone + PartialEq>(
@@ -214,7 +361,7 @@ fn has_riichi(_division: &Division, calls: &Vec<Call>, context: &HandContext) ->
 }
 
 fn has_ippatsu(_division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
-    no_open_calls(calls) && context.is_ippatsu
+    no_open_calls(calls) && (context.is_riichi || context.is_double_riichi) && context.is_ippatsu
 }
 
 fn has_haitei(_division: &Division, _calls: &Vec<Call>, context: &HandContext) -> bool {
@@ -237,6 +384,19 @@ fn has_double_riichi(_division: &Division, calls: &Vec<Call>, context: &HandCont
     no_open_calls(calls) && context.is_double_riichi
 }
 
+fn has_open_riichi(_division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
+    no_open_calls(calls)
+        && (context.is_riichi || context.is_double_riichi)
+        && context.is_open_riichi
+}
+
+fn has_open_riichi_deal_in(division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
+    context.rules.open_riichi_deal_in_yakuman
+        && has_open_riichi(division, calls, context)
+        && !context.is_tsumo
+        && context.is_non_riichi_deal_in
+}
+
 fn has_tenhou(_division: &Division, _calls: &Vec<Call>, context: &HandContext) -> bool {
     context.is_tenhou
 }
@@ -250,10 +410,9 @@ fn has_chiihou(_division: &Division, _calls: &Vec<Call>, context: &HandContext)
 fn has_iipeikou(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
     let melds = combine_melds(division, calls);
     no_open_calls(calls)
-        && melds
-            .iter()
-            .dedup()
-            .any(|m| is_sequence(m) && melds.iter().filter(|&mm| mm == m).count() >= 2)
+        && melds.iter().any(|m| {
+            m.is_sequence() && melds.iter().filter(|mm| mm.tiles() == m.tiles()).count() >= 2
+        })
 }
 
 fn has_tanyao(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
@@ -274,12 +433,19 @@ fn has_sha(division: &Division, calls: &Vec<Call>, context: &HandContext) -> boo
 }
 
 fn has_pei(division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
-    has_wind(tile_from_string("4z"), division, calls, context)
+    let pei = tile_from_string("4z");
+    match context.rules.sanma {
+        // North is always yakuhai, like the dragons
+        Some(SanmaRules { north: NorthRule::Yakuhai, .. }) => {
+            combine_melds(division, calls).iter().any(|m| m.tile() == pei)
+        }
+        _ => has_wind(pei, division, calls, context),
+    }
 }
 
 fn has_wind(wind: Tile, division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
     (wind == context.round_wind || wind == context.player_wind)
-        && combine_melds(division, calls).iter().any(|m| m[0] == wind)
+        && combine_melds(division, calls).iter().any(|m| m.tile() == wind)
 }
 
 fn has_haku(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
@@ -295,7 +461,7 @@ fn has_chun(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> b
 }
 
 fn has_colour(colour: Tile, division: &Division, calls: &Vec<Call>) -> bool {
-    combine_melds(division, calls).iter().any(|m| m[0] == colour)
+    combine_melds(division, calls).iter().any(|m| m.tile() == colour)
 }
 
 fn has_chanta(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
@@ -309,7 +475,9 @@ fn has_sanshoku_doujun(division: &Division, calls: &Vec<Call>, _context: &HandCo
         ["m", "p", "s"].iter().all(|s| {
             combine_melds(division, calls)
                 .iter()
-                .filter(|&m| *m == tiles_from_string(&format!("{}{}{}{}", n, n + 1, n + 2, s)))
+                .filter(|m| {
+                    m.tiles() == tiles_from_string(&format!("{}{}{}{}", n, n + 1, n + 2, s))
+                })
                 .count()
                 >= 1
         })
@@ -325,28 +493,18 @@ fn has_ittsu(division: &Division, calls: &Vec<Call>, _context: &HandContext) ->
         ["123", "456", "789"].iter().all(|n| {
             combine_melds(division, calls)
                 .iter()
-                .any(|m| *m == tiles_from_string(&format!("{}{}", n, s)))
+                .any(|m| m.tiles() == tiles_from_string(&format!("{}{}", n, s)))
         })
     })
 }
 
 fn has_toitoi(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
-    combine_melds(division, calls).iter().all(|meld| !is_sequence(meld))
+    combine_melds(division, calls).iter().all(|meld| !meld.is_sequence())
 }
 
-fn has_sanankou(division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
-    let closed_quads: Vec<Meld> =
-        calls.iter().filter(|c| c.ctype == CallType::Ankan).map(|c| c.meld()).collect();
-    let closed_triplets: Vec<Meld> =
-        division.melds.iter().filter(|m| is_triplet(m)).cloned().collect();
-    let count = closed_quads.len() + closed_triplets.len();
-    match count {
-        0 | 1 | 2 => false,
-        // Reject if final triplet/quad completed by ron
-        3 => context.is_tsumo || !closed_triplets.iter().any(|m| *m == context.winning_meld),
-        // Returns true for suuankou, which is not a problem
-        _ => true,
-    }
+fn has_sanankou(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
+    // Returns true for suuankou, which is not a problem
+    concealed_triplet_count(division, calls) >= 3
 }
 
 fn has_sanshoku_doukou(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
@@ -354,9 +512,9 @@ fn has_sanshoku_doukou(division: &Division, calls: &Vec<Call>, _context: &HandCo
         ["m", "p", "s"].iter().all(|s| {
             combine_melds(division, calls)
                 .iter()
-                .filter(|&m| {
-                    *m == tiles_from_string(&format!("{}{}{}{}", n, n, n, s))
-                        || *m == tiles_from_string(&format!("{}{}{}{}{}", n, n, n, n, s))
+                .filter(|m| {
+                    m.tiles() == tiles_from_string(&format!("{}{}{}{}", n, n, n, s))
+                        || m.tiles() == tiles_from_string(&format!("{}{}{}{}{}", n, n, n, n, s))
                 })
                 .count()
                 >= 1
@@ -376,7 +534,7 @@ fn has_honroutou(division: &Division, calls: &Vec<Call>, _context: &HandContext)
 fn has_shousangen(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
     combine_melds(division, calls)
         .iter()
-        .filter(|m| (is_triplet(m) || is_quadruplet(m)) && m[0].is_colour())
+        .filter(|m| m.is_triplet_or_quad() && m.tile().is_colour())
         .count()
         == 2
         && division.pair.len() >= 1
@@ -387,7 +545,10 @@ fn has_ryanpeikou(division: &Division, calls: &Vec<Call>, _context: &HandContext
     let melds = combine_melds(division, calls);
     melds
         .iter()
-        .filter(|m| is_sequence(m) && [2, 4].contains(&melds.iter().filter(|mm| mm == m).count()))
+        .filter(|m| {
+            m.is_sequence()
+                && [2, 4].contains(&melds.iter().filter(|mm| mm.tiles() == m.tiles()).count())
+        })
         .count()
         == 4
 }
@@ -430,17 +591,8 @@ fn has_kokushi(division: &Division, _calls: &Vec<Call>, _context: &HandContext)
         && terminals_honours.iter().any(|t| *t == pair_candidates[0])
 }
 
-fn has_suuankou(division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
-    let closed_quads: Vec<Meld> =
-        calls.iter().filter(|c| c.ctype == CallType::Ankan).map(|c| c.meld()).collect();
-    let closed_triplets: Vec<Meld> =
-        division.melds.iter().filter(|m| is_triplet(m)).cloned().collect();
-    let count = closed_quads.len() + closed_triplets.len();
-    match count {
-        // Reject if final triplet/quad completed by ron
-        4 => context.is_tsumo || !closed_triplets.iter().any(|m| *m == context.winning_meld),
-        _ => false,
-    }
+fn has_suuankou(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
+    concealed_triplet_count(division, calls) == 4
 }
 
 fn has_daisangen(division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
@@ -450,12 +602,12 @@ fn has_daisangen(division: &Division, calls: &Vec<Call>, context: &HandContext)
 }
 
 fn has_shousuushii(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
-    combine_melds(division, calls).iter().filter(|m| m[0].is_wind()).count() == 3
+    combine_melds(division, calls).iter().filter(|m| m.tile().is_wind()).count() == 3
         && division.pair[0].is_wind()
 }
 
 fn has_daisuushii(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
-    combine_melds(division, calls).iter().filter(|m| m[0].is_wind()).count() == 4
+    combine_melds(division, calls).iter().filter(|m| m.tile().is_wind()).count() == 4
 }
 
 fn has_tsuuiisou(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
@@ -496,6 +648,60 @@ fn has_suukantsu(_division: &Division, calls: &Vec<Call>, _context: &HandContext
     kan_count(calls) == 4
 }
 
+// Local yaku
+
+fn has_renhou(_division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
+    calls.is_empty() && !context.is_tsumo && context.is_renhou
+}
+
+fn has_sanrenkou(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
+    let triplets: Vec<Tile> = combine_melds(division, calls)
+        .iter()
+        .filter(|m| m.is_triplet_or_quad() && m.tile().is_number())
+        .map(|m| m.tile())
+        .collect();
+    triplets.iter().any(|t| {
+        t.number() <= 7 && triplets.contains(&t.next()) && triplets.contains(&t.next().next())
+    })
+}
+
+fn has_daisharin(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
+    calls.is_empty()
+        && flatten_tiles(division, calls).iter().sorted().cloned().collect::<Vec<Tile>>()
+            == tiles_from_string("22334455667788p")
+}
+
+fn has_iisou_suushun(division: &Division, calls: &Vec<Call>, _context: &HandContext) -> bool {
+    no_open_calls(calls)
+        && division.melds.len() == 4
+        && division.melds.iter().all(|m| m.is_sequence() && m.tiles() == division.melds[0].tiles())
+}
+
+// Thirteen unrelated tiles and a pair in the starting hand
+fn has_shiisanpuutaa(division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
+    let tiles = &division.remaining;
+    if !calls.is_empty() || tiles.len() != 14 || !(context.is_tenhou || context.is_chiihou) {
+        return false;
+    }
+    let distinct: Vec<Tile> = tiles.iter().sorted().dedup().cloned().collect();
+    let is_related = |a: &Tile, b: &Tile| {
+        a.is_number()
+            && b.is_number()
+            && a.is_man() == b.is_man()
+            && a.is_pin() == b.is_pin()
+            && (a.number() as i8 - b.number() as i8).abs() <= 2
+    };
+    distinct.len() == 13 && distinct.iter().tuple_combinations().all(|(a, b)| !is_related(a, b))
+}
+
+fn has_daichisei(division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
+    has_chiitoi(division, calls, context) && division.remaining.iter().all(|t| t.is_honour())
+}
+
+fn has_ishinouenimosannen(_division: &Division, calls: &Vec<Call>, context: &HandContext) -> bool {
+    no_open_calls(calls) && context.is_double_riichi && (context.is_haitei || context.is_houtei)
+}
+
 // Helpers
 
 fn placeholder(_division: &Division, _calls: &Vec<Call>, _context: &HandContext) -> bool {
@@ -506,10 +712,18 @@ fn kan_count(calls: &Vec<Call>) -> usize {
     calls.iter().filter(|c| [CallType::Minkan, CallType::Ankan].contains(&c.ctype)).count()
 }
 
+// Counts closed quads and concealed triplets (a triplet completed by ron is not concealed)
+fn concealed_triplet_count(division: &Division, calls: &[Call]) -> usize {
+    let closed_quads = calls.iter().filter(|c| c.ctype == CallType::Ankan).count();
+    let closed_triplets =
+        division.melds.iter().filter(|m| m.kind() == MeldKind::Triplet && m.is_concealed()).count();
+    closed_quads + closed_triplets
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::division::divide;
+    use crate::{division::divide, types::Rules};
 
     #[test]
     fn menzen_tsumo() {
@@ -977,6 +1191,156 @@ mod tests {
         );
     }
 
+    #[test]
+    fn renhou() {
+        let tiles = tiles_from_string("234456m66p12344s");
+        let context = HandContext { is_renhou: true, ..ctw("4s", false, "1z", "2z") };
+
+        assert_any_division(has_renhou, &tiles, &vec![], &context);
+        // Tsumo
+        refute_all_divisions(
+            has_renhou,
+            &tiles,
+            &vec![],
+            &HandContext { is_tsumo: true, ..context },
+        );
+        refute_all_divisions(has_renhou, &tiles, &vec![], &ctw("4s", false, "1z", "2z"));
+    }
+
+    #[test]
+    fn sanrenkou() {
+        let tiles = tiles_from_string("3334445556p78s");
+        assert_any_division(has_sanrenkou, &tiles, &vec![], &ct("6p", true));
+
+        let calls = vec![c("p", "7m"), c("ck", "8m")];
+        assert_any_division(
+            has_sanrenkou,
+            &tiles_from_string("666m11p78s"),
+            &calls,
+            &ct("9s", false),
+        );
+        // Not consecutive
+        let calls = vec![c("p", "7m"), c("ck", "9m")];
+        refute_all_divisions(
+            has_sanrenkou,
+            &tiles_from_string("666m11p78s"),
+            &calls,
+            &ct("9s", false),
+        );
+    }
+
+    #[test]
+    fn open_riichi() {
+        let tiles = tiles_from_string("234456m66p12344s");
+        let context = HandContext { is_riichi: true, is_open_riichi: true, ..ct("4s", false) };
+
+        assert_any_division(has_open_riichi, &tiles, &vec![], &context);
+        let double = HandContext { is_riichi: false, is_double_riichi: true, ..context.clone() };
+        assert_any_division(has_open_riichi, &tiles, &vec![], &double);
+        // Open riichi requires riichi
+        let no_riichi = HandContext { is_riichi: false, ..context };
+        refute_all_divisions(has_open_riichi, &tiles, &vec![], &no_riichi);
+    }
+
+    #[test]
+    fn open_riichi_deal_in() {
+        let tiles = tiles_from_string("234456m66p12344s");
+        let context = HandContext {
+            is_riichi: true,
+            is_open_riichi: true,
+            is_non_riichi_deal_in: true,
+            rules: Rules { open_riichi_deal_in_yakuman: true, ..Default::default() },
+            ..ct("4s", false)
+        };
+
+        assert_any_division(has_open_riichi_deal_in, &tiles, &vec![], &context);
+        // The rule is off
+        let off = HandContext { rules: Rules::default(), ..context.clone() };
+        refute_all_divisions(has_open_riichi_deal_in, &tiles, &vec![], &off);
+        // Dealt in by a player in riichi
+        let riichi = HandContext { is_non_riichi_deal_in: false, ..context.clone() };
+        refute_all_divisions(has_open_riichi_deal_in, &tiles, &vec![], &riichi);
+        // Won by tsumo
+        let tsumo = HandContext { is_tsumo: true, ..context };
+        refute_all_divisions(has_open_riichi_deal_in, &tiles, &vec![], &tsumo);
+    }
+
+    #[test]
+    fn daisharin() {
+        let tiles = tiles_from_string("2233445566778p");
+        assert_any_division(has_daisharin, &tiles, &vec![], &ct("8p", false));
+        // Same shape in sou
+        refute_all_divisions(
+            has_daisharin,
+            &tiles_from_string("2233445566778s"),
+            &vec![],
+            &ct("8s", false),
+        );
+        // Chiitoitsu interpretation
+        assert!(has_daisharin(
+            &Division {
+                pair: vec![],
+                melds: vec![],
+                remaining: tiles_from_string("22334455667788p")
+            },
+            &vec![],
+            &ct("8p", false)
+        ));
+    }
+
+    #[test]
+    fn iisou_suushun() {
+        let tiles = tiles_from_string("44445555666p11z");
+        assert_any_division(has_iisou_suushun, &tiles, &vec![], &ct("6p", true));
+        let tiles = tiles_from_string("4445556667p11z");
+        refute_all_divisions(has_iisou_suushun, &tiles, &vec![], &ct("7p", true));
+    }
+
+    #[test]
+    fn shiisanpuutaa() {
+        let tiles = tiles_from_string("147m258p369s1234z");
+        let division = |winning_tile: &str| {
+            let mut remaining = tiles.clone();
+            remaining.push(tile_from_string(winning_tile));
+            Division { pair: vec![], melds: vec![], remaining }
+        };
+        let context = HandContext { is_tenhou: true, ..ct("4z", true) };
+
+        assert!(has_shiisanpuutaa(&division("4z"), &vec![], &context));
+        // Not the starting hand
+        assert!(!has_shiisanpuutaa(&division("4z"), &vec![], &ct("4z", true)));
+        // Two honour pairs
+        let mut two_pairs = division("4z");
+        two_pairs.remaining[9] = tile_from_string("2z");
+        assert!(!has_shiisanpuutaa(&two_pairs, &vec![], &context));
+        // 3-man and 1-man are related (kanchan)
+        assert!(!has_shiisanpuutaa(&division("3m"), &vec![], &context));
+    }
+
+    #[test]
+    fn daichisei() {
+        let division = |tiles: &str| Division {
+            pair: vec![],
+            melds: vec![],
+            remaining: tiles_from_string(tiles),
+        };
+        assert!(has_daichisei(&division("11223344556677z"), &vec![], &ct("7z", true)));
+        assert!(!has_daichisei(&division("11m223344556677z"), &vec![], &ct("7z", true)));
+    }
+
+    #[test]
+    fn ishinouenimosannen() {
+        let tiles = tiles_from_string("234456m66p12344s");
+        let context = HandContext { is_double_riichi: true, is_haitei: true, ..ct("4s", true) };
+
+        assert_any_division(has_ishinouenimosannen, &tiles, &vec![], &context);
+        let houtei = HandContext { is_haitei: false, is_houtei: true, ..context.clone() };
+        assert_any_division(has_ishinouenimosannen, &tiles, &vec![], &houtei);
+        // Regular riichi
+        let riichi = HandContext { is_double_riichi: false, is_riichi: true, ..context };
+        refute_all_divisions(has_ishinouenimosannen, &tiles, &vec![], &riichi);
+    }
+
     fn assert_any_division(
         func: CheckFunc, tiles: &Vec<Tile>, calls: &Vec<Call>, context: &HandContext,
     ) {
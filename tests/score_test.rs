@@ -3,9 +3,14 @@
 extern crate toitoi;
 use itertools::Itertools;
 use toitoi::{
-    score::{score, ScoreResult},
+    custom_yaku::{CustomYaku, YakuRegistry},
+    diagnosis::{try_score, ScoreFailure},
+    score::{is_kataagari, score, ScoreResult},
     tile::{tile_from_string, tiles_from_string},
-    types::{Call, FuReason, HanReason, HandContext, Limit, Points, Yaku, Yakuman},
+    types::{
+        Call, FuReason, HanReason, HandContext, Interpretation, Limit, LimitThresholds, MeldKind,
+        MeldOrigin, NorthRule, Points, Rules, SanmaPayment, SanmaRules, Wait, Yaku, Yakuman,
+    },
 };
 
 // ---- 0 han (for testing fu)
@@ -994,6 +999,604 @@ fn score_tenhou_toitoi_sanshoku_doukou() {
     );
 }
 
+// ---- Melds
+
+#[test]
+fn score_melds_origin() {
+    let results = score(
+        &tiles_from_string("111m22p333s55z"),
+        &vec![Call::pon(tile_from_string("7z"))],
+        &ct("2p", false),
+    );
+
+    assert_eq!(results.len(), 1);
+    let melds = results[0].melds();
+    assert_eq!(
+        melds.iter().map(|m| (m.kind(), m.origin())).collect::<Vec<_>>(),
+        vec![
+            (MeldKind::Triplet, MeldOrigin::Concealed),
+            (MeldKind::Triplet, MeldOrigin::Concealed),
+            (MeldKind::Triplet, MeldOrigin::Ron),
+            (MeldKind::Triplet, MeldOrigin::Called),
+        ]
+    );
+    assert!(melds[3].is_open());
+    assert_eq!(melds[2].tiles(), tiles_from_string("222p").as_slice());
+    let pair = results[0].pair().unwrap();
+    assert_eq!((pair.tile(), pair.origin()), (tile_from_string("5z"), MeldOrigin::Concealed));
+}
+
+#[test]
+fn score_melds_tanki_pair() {
+    let results = score(&tiles_from_string("123m456p789s1112z"), &vec![], &ct("2z", false));
+
+    assert_eq!(results.len(), 1);
+    let pair = results[0].pair().unwrap();
+    assert_eq!(pair.kind(), MeldKind::Pair);
+    assert_eq!(pair.origin(), MeldOrigin::Ron);
+    assert!(results[0].melds().iter().all(|m| m.is_concealed()));
+}
+
+// ---- Result details
+
+#[test]
+fn score_details_wait_and_winning_meld() {
+    let results = score(&tiles_from_string("234789m1234566p"), &vec![], &ctw("6p", false, "1z", "2z"));
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].wait(), Wait::Tanki);
+    assert_eq!(results[0].winning_meld().unwrap().kind(), MeldKind::Pair);
+    assert_eq!(results[1].wait(), Wait::Ryanmen);
+    assert_eq!(results[1].winning_meld().unwrap().tiles(), tiles_from_string("456p").as_slice());
+    assert!(results.iter().all(|r| r.is_closed() && !r.is_tsumo() && !r.is_dealer()));
+}
+
+#[test]
+fn score_details_open_dealer_tsumo() {
+    let results = score(
+        &tiles_from_string("1233p567s"),
+        &vec![Call::pon(tile_from_string("6z")), Call::pon(tile_from_string("7z"))],
+        &ctw("3p", true, "1z", "1z"),
+    );
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(
+        results.iter().map(|r| r.wait()).collect::<Vec<_>>(),
+        vec![Wait::Tanki, Wait::Penchan]
+    );
+    assert!(results.iter().all(|r| !r.is_closed() && r.is_tsumo() && r.is_dealer()));
+}
+
+#[test]
+fn score_details_chiitoi_kokushi() {
+    let results = score(&tiles_from_string("113344m55p22s335z"), &vec![], &ct("5z", false));
+    assert_eq!(results[0].wait(), Wait::Tanki);
+    assert_eq!(results[0].winning_meld(), None);
+
+    let results = score(&tiles_from_string("19m19p19s1234567z"), &vec![], &ct("1z", true));
+    assert_eq!(results[0].wait(), Wait::Kokushi13);
+}
+
+// ---- Open riichi
+
+#[test]
+fn score_open_riichi() {
+    let tiles = tiles_from_string("234456m66p12344s");
+    let context = HandContext {
+        is_riichi: true,
+        is_open_riichi: true,
+        is_ippatsu: true,
+        rules: local_rules(),
+        ..ctw("4s", false, "1z", "2z")
+    };
+    let results = score(&tiles, &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    check_han_reasons(
+        &results[0].han_reasons(),
+        vec![
+            HanReason::Yaku(Yaku::Riichi),
+            HanReason::Yaku(Yaku::Ippatsu),
+            HanReason::Yaku(Yaku::OpenRiichi),
+        ],
+    );
+}
+
+#[test]
+fn score_open_double_riichi() {
+    let tiles = tiles_from_string("234456m66p12344s");
+    // Double riichi replaces riichi if both are given
+    let context = HandContext {
+        is_riichi: true,
+        is_double_riichi: true,
+        is_open_riichi: true,
+        rules: local_rules(),
+        ..ctw("4s", false, "1z", "2z")
+    };
+    let results = score(&tiles, &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    check_han_reasons(
+        &results[0].han_reasons(),
+        vec![HanReason::Yaku(Yaku::DoubleRiichi), HanReason::Yaku(Yaku::OpenRiichi)],
+    );
+}
+
+#[test]
+fn score_open_riichi_without_riichi() {
+    // Neither open riichi nor ippatsu count without riichi
+    let context = HandContext {
+        is_open_riichi: true,
+        is_ippatsu: true,
+        rules: local_rules(),
+        ..ct("4s", false)
+    };
+
+    let results = score(&tiles_from_string("234456m66p12344s"), &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].han(), 0);
+}
+
+#[test]
+fn score_open_riichi_deal_in_yakuman() {
+    let tiles = tiles_from_string("234456m66p12344s");
+    let context = HandContext {
+        is_riichi: true,
+        is_open_riichi: true,
+        is_non_riichi_deal_in: true,
+        rules: Rules { open_riichi_deal_in_yakuman: true, ..local_rules() },
+        ..ctw("4s", false, "1z", "2z")
+    };
+    let results = score(&tiles, &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    check_han_reasons(
+        &results[0].han_reasons(),
+        vec![HanReason::Yakuman(Yakuman::OpenRiichiDealIn)],
+    );
+    assert_eq!(results[0].points(), Points::Ron(32000));
+
+    // Without the rule, it is riichi and open riichi only
+    let results = score(&tiles, &vec![], &HandContext { rules: local_rules(), ..context.clone() });
+    assert_eq!(results[0].points(), Points::Ron(2600));
+
+    // Without local yaku, it is riichi only
+    let results = score(&tiles, &vec![], &HandContext { rules: Rules::default(), ..context });
+    assert_eq!(results[0].points(), Points::Ron(1300));
+}
+
+// ---- Limits
+
+#[test]
+fn score_kiriage_mangan_4han_30fu() {
+    let tiles = tiles_from_string("123m456p789s55p23s");
+    let context = HandContext { is_riichi: true, n_dora: 2, ..ctw("4s", false, "1z", "2z") };
+
+    let results = score(&tiles, &vec![], &context);
+    assert_eq!(results.len(), 1);
+    assert_eq!((results[0].han(), results[0].fu()), (4, 30));
+    assert_eq!(results[0].limit(), Limit::NoLimit);
+    assert_eq!(results[0].points(), Points::Ron(7700));
+
+    let kiriage =
+        HandContext { rules: Rules { kiriage_mangan: true, ..Default::default() }, ..context };
+    let results = score(&tiles, &vec![], &kiriage);
+    assert_eq!(results[0].limit(), Limit::Mangan);
+    assert_eq!(results[0].points(), Points::Ron(8000));
+}
+
+#[test]
+fn score_kiriage_mangan_3han_60fu() {
+    let tiles = tiles_from_string("111m456p78s55s");
+    let calls = vec![Call::ankan(tile_from_string("2s"))];
+    let context = HandContext { is_riichi: true, n_dora: 2, ..ctw("9s", false, "1z", "2z") };
+
+    let results = score(&tiles, &calls, &context);
+    assert_eq!(results.len(), 1);
+    assert_eq!((results[0].han(), results[0].fu()), (3, 60));
+    assert_eq!(results[0].points(), Points::Ron(7700));
+
+    let kiriage =
+        HandContext { rules: Rules { kiriage_mangan: true, ..Default::default() }, ..context };
+    let results = score(&tiles, &calls, &kiriage);
+    assert_eq!(results[0].limit(), Limit::Mangan);
+    assert_eq!(results[0].points(), Points::Ron(8000));
+}
+
+#[test]
+fn score_custom_limits() {
+    let tiles = tiles_from_string("123m456p789s55p23s");
+    let limits = LimitThresholds { kazoe_yakuman: 14, ..Default::default() };
+    let context = HandContext {
+        is_riichi: true,
+        n_dora: 11,
+        rules: Rules { limits, ..Default::default() },
+        ..ctw("4s", false, "1z", "2z")
+    };
+
+    // 13 han is capped at sanbaiman
+    let results = score(&tiles, &vec![], &context);
+    assert_eq!(results[0].han(), 13);
+    assert_eq!(results[0].limit(), Limit::Sanbaiman);
+
+    // Yakuman is unaffected
+    let results = score(
+        &tiles_from_string("1122334455667z"),
+        &vec![],
+        &HandContext { winning_tile: tile_from_string("7z"), ..context },
+    );
+    assert_eq!(results[0].limit(), Limit::Yakuman);
+}
+
+#[test]
+fn score_custom_limits_below_mangan() {
+    // 4 han 40 fu is not mangan, but is still paid no more than mangan
+    let tiles = tiles_from_string("123m456p789s55p13s");
+    let limits = LimitThresholds { mangan_4_han_fu: 50, ..Default::default() };
+    let context = HandContext {
+        is_riichi: true,
+        n_dora: 3,
+        rules: Rules { limits, ..Default::default() },
+        ..ctw("2s", false, "1z", "2z")
+    };
+
+    let results = score(&tiles, &vec![], &context);
+    assert_eq!((results[0].han(), results[0].fu()), (4, 40));
+    assert_eq!(results[0].limit(), Limit::NoLimit);
+    assert_eq!(results[0].points(), Points::Ron(8000));
+}
+
+// ---- Aotenjou
+
+#[test]
+fn score_aotenjou() {
+    let tiles = tiles_from_string("123m456p789s55p23s");
+    let context = HandContext {
+        is_riichi: true,
+        n_dora: 4,
+        rules: aotenjou_rules(),
+        ..ctw("4s", false, "1z", "2z")
+    };
+    let results = score(&tiles, &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    assert_eq!((results[0].han(), results[0].fu()), (6, 30));
+    assert_eq!(results[0].limit(), Limit::NoLimit);
+    // 30 * 2^8 = 7680 base points
+    assert_eq!(results[0].points(), Points::Ron(30800));
+}
+
+#[test]
+fn score_aotenjou_yakuman_with_yaku() {
+    let tiles = tiles_from_string("1122334455667z");
+    let context =
+        HandContext { is_riichi: true, rules: aotenjou_rules(), ..ctw("7z", false, "1z", "2z") };
+    let results = score(&tiles, &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    check_han_reasons(
+        &results[0].han_reasons(),
+        vec![
+            HanReason::Yakuman(Yakuman::Tsuuiisou),
+            HanReason::Yaku(Yaku::Riichi),
+            HanReason::Yaku(Yaku::Chiitoitsu),
+            HanReason::Yaku(Yaku::Honroutou),
+            HanReason::Yaku(Yaku::Honitsu),
+        ],
+    );
+    assert_eq!((results[0].han(), results[0].fu()), (21, 25));
+    assert_eq!(results[0].points(), Points::Ron(838860800));
+}
+
+#[test]
+fn score_aotenjou_kokushi() {
+    let tiles = tiles_from_string("19m19p19s1234567z");
+    let context =
+        HandContext { is_tenhou: true, rules: aotenjou_rules(), ..ctw("1z", true, "1z", "1z") };
+    let results = score(&tiles, &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    check_han_reasons(
+        &results[0].han_reasons(),
+        vec![
+            HanReason::Yakuman(Yakuman::KokushiMusou),
+            HanReason::Yakuman(Yakuman::Tenhou),
+            HanReason::Yaku(Yaku::MenzenTsumo),
+        ],
+    );
+    assert_eq!((results[0].han(), results[0].fu()), (27, 30));
+    assert_eq!(results[0].points(), Points::TsumoAll(32212254800));
+    assert_eq!(results[0].points_total(), 96636764400);
+}
+
+// ---- Custom yaku
+
+// Chiitoitsu of only odd-numbered tiles
+struct OddPairs;
+
+impl CustomYaku for OddPairs {
+    fn name(&self) -> &'static str { "Odd pairs" }
+
+    fn is_yakuman(&self) -> bool { true }
+
+    fn han_closed(&self) -> u8 { 13 }
+
+    fn han_open(&self) -> u8 { 0 }
+
+    fn check(&self, hand: &Interpretation, _context: &HandContext) -> bool {
+        match hand {
+            Interpretation::Chiitoitsu { pairs } => {
+                pairs.iter().all(|t| t.is_number() && t.number() % 2 == 1)
+            }
+            _ => false,
+        }
+    }
+}
+
+// A pon of green dragons, which are lucky, in place of hatsu
+struct LuckyGreen;
+
+impl CustomYaku for LuckyGreen {
+    fn name(&self) -> &'static str { "Lucky green" }
+
+    fn han_closed(&self) -> u8 { 3 }
+
+    fn han_open(&self) -> u8 { 2 }
+
+    fn supersedes(&self) -> Vec<HanReason> { vec![HanReason::Yaku(Yaku::Hatsu)] }
+
+    fn check(&self, hand: &Interpretation, _context: &HandContext) -> bool {
+        match hand {
+            Interpretation::Standard { melds, .. } => {
+                melds.iter().any(|m| m.is_triplet_or_quad() && m.tile() == tile_from_string("6z"))
+            }
+            _ => false,
+        }
+    }
+}
+
+fn custom_rules() -> Rules {
+    let mut registry = YakuRegistry::new();
+    registry.register(OddPairs);
+    registry.register(LuckyGreen);
+    Rules { custom_yaku: registry, ..Default::default() }
+}
+
+#[test]
+fn score_custom_yakuman() {
+    let context = HandContext { is_riichi: true, rules: custom_rules(), ..ct("3s", false) };
+    let results = score(&tiles_from_string("1133m5577p1199s3s"), &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].han_reasons(), vec![(HanReason::CustomYakuman("Odd pairs"), 13)]);
+    assert_eq!(results[0].limit(), Limit::Yakuman);
+    assert_eq!(results[0].points(), Points::Ron(48000));
+}
+
+#[test]
+fn score_custom_yaku_supersedes_builtin() {
+    let calls = vec![Call::pon(tile_from_string("6z"))];
+    let context = HandContext { rules: custom_rules(), ..ctw("9s", false, "1z", "2z") };
+    let results = score(&tiles_from_string("123m456p78s55s"), &calls, &context);
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].han_reasons(), vec![(HanReason::CustomYaku("Lucky green"), 2)]);
+    assert_eq!(results[0].points(), Points::Ron(2000));
+
+    // Without the registry, it is just hatsu
+    let results =
+        score(&tiles_from_string("123m456p78s55s"), &calls, &ctw("9s", false, "1z", "2z"));
+    assert_eq!(results[0].han_reasons(), vec![(HanReason::Yaku(Yaku::Hatsu), 1)]);
+}
+
+// ---- Diagnosis
+
+#[test]
+fn try_score_dora_only() {
+    let calls = vec![Call::chi(tile_from_string("1m"))];
+    let context = HandContext { n_dora: 2, n_akadora: 1, ..ctw("4s", false, "1z", "2z") };
+    let tiles = tiles_from_string("456p789s23s99p");
+
+    // `score` counts the dora, but they are not a yaku
+    assert_eq!(score(&tiles, &calls, &context)[0].han(), 3);
+    assert_eq!(try_score(&tiles, &calls, &context, None), Err(ScoreFailure::NoYaku));
+
+    let context = HandContext { n_dora: 2, ..ctw("4s", true, "1z", "2z") };
+    let results = try_score(&tiles_from_string("456p23s222z99p"), &calls, &context, None).unwrap();
+    assert_eq!(results[0].han_reasons()[0], (HanReason::Yaku(Yaku::Nan), 1));
+}
+
+// ---- Kataagari
+
+#[test]
+fn score_kataagari() {
+    let calls = vec![Call::chi(tile_from_string("2m"))];
+    let tiles = tiles_from_string("456p678s55s78m");
+    let rules = Rules { reject_kataagari: true, ..Default::default() };
+
+    // Tanyao on 6-man only
+    assert!(is_kataagari(&tiles, &calls, &ctw("9m", false, "1z", "2z")));
+    assert_eq!(score(&tiles, &calls, &ctw("9m", false, "1z", "2z"))[0].han(), 0);
+    assert_eq!(score(&tiles, &calls, &ctw("6m", false, "1z", "2z"))[0].han(), 1);
+
+    let context = HandContext { rules, ..ctw("6m", false, "1z", "2z") };
+    assert!(score(&tiles, &calls, &context).is_empty());
+    assert_eq!(try_score(&tiles, &calls, &context, None), Err(ScoreFailure::Kataagari));
+}
+
+#[test]
+fn score_kataagari_closed() {
+    // Riichi covers every wait
+    let tiles = tiles_from_string("123m456p789s55s78m");
+    let rules = Rules { reject_kataagari: true, ..Default::default() };
+    let context = HandContext { is_riichi: true, rules, ..ctw("9m", false, "1z", "2z") };
+
+    assert!(!is_kataagari(&tiles, &vec![], &context));
+    assert_eq!(
+        score(&tiles, &vec![], &context)[0].han_reasons(),
+        vec![(HanReason::Yaku(Yaku::Riichi), 1), (HanReason::Yaku(Yaku::Pinfu), 1)]
+    );
+}
+
+// ---- Local yaku
+
+#[test]
+fn score_local_yaku_toggle() {
+    let tiles = tiles_from_string("333444555p6799s");
+    let local = HandContext { rules: local_rules(), ..ct("8s", false) };
+
+    let results = score(&tiles, &vec![], &local);
+    assert!(results.iter().any(|r| r
+        .han_reasons()
+        .iter()
+        .any(|(reason, han)| *reason == HanReason::Yaku(Yaku::Sanrenkou) && *han == 2)));
+    let results = score(&tiles, &vec![], &ct("8s", false));
+    assert!(results.iter().all(|r| !r
+        .han_reasons()
+        .iter()
+        .any(|(reason, _)| *reason == HanReason::Yaku(Yaku::Sanrenkou))));
+}
+
+#[test]
+fn score_local_renhou() {
+    let context =
+        HandContext { is_renhou: true, rules: local_rules(), ..ctw("4s", false, "1z", "2z") };
+    let results = score(&tiles_from_string("234456m66p12344s"), &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].limit(), Limit::Mangan);
+    assert_eq!(results[0].points(), Points::Ron(8000));
+}
+
+#[test]
+fn score_local_daichisei_supersedes_tsuuiisou() {
+    let context = HandContext { rules: local_rules(), ..ct("7z", true) };
+    let results = score(&tiles_from_string("1122334455667z"), &vec![], &context);
+
+    assert_eq!(results.len(), 1);
+    check_han_reasons(&results[0].han_reasons(), vec![HanReason::Yakuman(Yakuman::Daichisei)]);
+
+    let results = score(&tiles_from_string("1122334455667z"), &vec![], &ct("7z", true));
+    check_han_reasons(&results[0].han_reasons(), vec![HanReason::Yakuman(Yakuman::Tsuuiisou)]);
+}
+
+#[test]
+fn score_local_shiisanpuutaa() {
+    let tiles = tiles_from_string("147m258p369s1234z");
+    let context = HandContext { is_tenhou: true, rules: local_rules(), ..ct("4z", true) };
+
+    let results = score(&tiles, &vec![], &context);
+    assert_eq!(results.len(), 1);
+    check_han_reasons(&results[0].han_reasons(), vec![HanReason::Yakuman(Yakuman::Shiisanpuutaa)]);
+    assert_eq!(results[0].points(), Points::TsumoAll(16000));
+
+    assert!(score(&tiles, &vec![], &HandContext { is_tenhou: true, ..ct("4z", true) }).is_empty());
+}
+
+// ---- Sanma
+
+#[test]
+fn score_sanma_invalid() {
+    let context = ctsanma("4s", true, "2z", SanmaRules::default());
+    assert!(score(&tiles_from_string("55m456p678s23s555z"), &vec![], &context).is_empty());
+    assert!(!score(&tiles_from_string("11m456p678s23s555z"), &vec![], &context).is_empty());
+    assert!(score(
+        &tiles_from_string("11m678s23s555z"),
+        &vec![Call::chi(tile_from_string("4p"))],
+        &context
+    )
+    .is_empty());
+}
+
+#[test]
+fn score_sanma_north() {
+    let tiles = tiles_from_string("11m456p678s23s444z");
+    let yakuhai = SanmaRules { north: NorthRule::Yakuhai, ..Default::default() };
+    let results = score(&tiles, &vec![], &ctsanma("4s", false, "2z", yakuhai));
+    assert_eq!(results[0].han_reasons(), vec![(HanReason::Yaku(Yaku::Pei), 1)]);
+
+    let results = score(&tiles, &vec![], &ctsanma("4s", false, "2z", SanmaRules::default()));
+    assert_eq!(results[0].han(), 0);
+}
+
+#[test]
+fn score_sanma_north_pair() {
+    let tiles = tiles_from_string("123p456p678s23s44z");
+    let yakuhai = SanmaRules { north: NorthRule::Yakuhai, ..Default::default() };
+    let results = score(&tiles, &vec![], &ctsanma("4s", false, "2z", yakuhai));
+    assert_eq!(results[0].fu(), 40);
+    check_fu_reasons(
+        &results[0].fu_reasons(),
+        vec![FuReason::Base, FuReason::ClosedRon, FuReason::YakuhaiPairNorth],
+    );
+
+    // The north pair is not yakuhai, so this is pinfu
+    let results = score(&tiles, &vec![], &ctsanma("4s", false, "2z", SanmaRules::default()));
+    assert_eq!(results[0].fu(), 30);
+}
+
+#[test]
+fn score_sanma_payments() {
+    // Menzen tsumo and haku, 2 han 30 fu
+    let tiles = tiles_from_string("11m456p678s23s555z");
+    let points = |player_wind, payment| {
+        let sanma = SanmaRules { payment, ..Default::default() };
+        let results = score(&tiles, &vec![], &ctsanma("4s", true, player_wind, sanma));
+        (results[0].points(), results[0].points_total())
+    };
+
+    assert_eq!(points("2z", SanmaPayment::TsumoLoss), (Points::Tsumo(500, 1000), 1500));
+    assert_eq!(points("2z", SanmaPayment::NorthBisection), (Points::Tsumo(800, 1300), 2100));
+    assert_eq!(points("2z", SanmaPayment::TsumoLossFree), (Points::Tsumo(800, 1200), 2000));
+    assert_eq!(points("1z", SanmaPayment::TsumoLoss), (Points::TsumoAll(1000), 2000));
+    assert_eq!(points("1z", SanmaPayment::NorthBisection), (Points::TsumoAll(1500), 3000));
+    assert_eq!(points("1z", SanmaPayment::TsumoLossFree), (Points::TsumoAll(1500), 3000));
+}
+
+#[test]
+fn score_sanma_payments_mangan() {
+    let tiles = tiles_from_string("11m456p678s23s555z");
+    let points = |payment| {
+        let sanma = SanmaRules { payment, ..Default::default() };
+        let context = HandContext { n_dora: 3, ..ctsanma("4s", true, "3z", sanma) };
+        score(&tiles, &vec![], &context)[0].points()
+    };
+
+    assert_eq!(points(SanmaPayment::TsumoLoss), Points::Tsumo(2000, 4000));
+    assert_eq!(points(SanmaPayment::NorthBisection), Points::Tsumo(3000, 5000));
+    assert_eq!(points(SanmaPayment::TsumoLossFree), Points::Tsumo(3000, 5000));
+}
+
+#[test]
+fn score_sanma_nukidora() {
+    let tiles = tiles_from_string("11m456p678s23s555z");
+    let context = HandContext {
+        n_nukidora: 2,
+        // One dora indicator points to north, the other does not
+        dora_indicators: tiles_from_string("3z9s"),
+        uradora_indicators: tiles_from_string("3z"),
+        ..ctsanma("4s", false, "2z", SanmaRules::default())
+    };
+
+    let results = score(&tiles, &vec![], &context);
+    assert_eq!(results.len(), 1);
+    check_han_reasons(
+        &results[0].han_reasons(),
+        vec![
+            HanReason::Yaku(Yaku::Haku),
+            HanReason::Yaku(Yaku::Nukidora),
+            HanReason::Yaku(Yaku::Nukidora),
+            HanReason::Yaku(Yaku::Dora),
+            HanReason::Yaku(Yaku::Dora),
+            HanReason::Yaku(Yaku::Uradora),
+            HanReason::Yaku(Yaku::Uradora),
+        ],
+    );
+    // Nukidora are not part of the hand
+    assert_eq!(results[0].fu(), 40);
+}
+
 // ---- Helpers
 
 fn check(
@@ -1032,6 +1635,19 @@ fn ct(winning_tile: &str, is_tsumo: bool) -> HandContext {
     HandContext { winning_tile: tile_from_string(winning_tile), is_tsumo, ..Default::default() }
 }
 
+fn aotenjou_rules() -> Rules { Rules { aotenjou: true, ..Default::default() } }
+
+fn local_rules() -> Rules { Rules { local_yaku: true, ..Default::default() } }
+
+fn ctsanma(
+    winning_tile: &str, is_tsumo: bool, player_wind: &str, sanma: SanmaRules,
+) -> HandContext {
+    HandContext {
+        rules: Rules { sanma: Some(sanma), ..Default::default() },
+        ..ctw(winning_tile, is_tsumo, "1z", player_wind)
+    }
+}
+
 fn ctw(winning_tile: &str, is_tsumo: bool, round_wind: &str, player_wind: &str) -> HandContext {
     HandContext {
         winning_tile: tile_from_string(winning_tile),